@@ -0,0 +1,51 @@
+use crate::bigint::*;
+
+#[test]
+fn biguint_arithmetic_test() {
+    let a: BigUint = "123456789012345678901234567890".parse().unwrap();
+    let b: BigUint = "987654321098765432109876543210".parse().unwrap();
+
+    assert_eq!((&a + &b).to_string(), "1111111110111111111011111111100");
+    assert_eq!((&b - &a).to_string(), "864197532086419753208641975320");
+    assert_eq!((&a * &b).to_string(), "121932631137021795226185032733622923332237463801111263526900");
+    assert_eq!((&a - &a), BigUint::zero());
+    assert_eq!(BigUint::from(u64::MAX).to_string(), "18446744073709551615");
+    assert_eq!(BigUint::from(u64::MAX).to_u64(), Some(u64::MAX));
+    assert_eq!((&BigUint::from(u64::MAX) + &BigUint::one()).to_u64(), None);
+    assert_eq!(BigUint::zero().to_string(), "0");
+    assert_eq!(BigUint::from(1 << 40).bits(), 41);
+}
+
+#[test]
+fn biguint_order_test() {
+    let small: BigUint = "4294967296".parse().unwrap();
+    let large: BigUint = "18446744073709551616".parse().unwrap();
+
+    assert!(small < large);
+    assert!(BigUint::zero() < small);
+    assert_eq!(large.div_rem_small(1000), ("18446744073709551".parse().unwrap(), 616));
+    assert!("12a".parse::<BigUint>().is_err());
+    assert!("".parse::<BigUint>().is_err());
+}
+
+#[test]
+#[should_panic]
+fn biguint_underflow_test() {
+    let _ = &BigUint::one() - &BigUint::from(2);
+}
+
+#[test]
+fn bigint_arithmetic_test() {
+    let a = BigInt::from(-7);
+    let b = BigInt::from(12);
+
+    assert_eq!((&a + &b).to_string(), "5");
+    assert_eq!((&a - &b).to_string(), "-19");
+    assert_eq!((&a * &b).to_string(), "-84");
+    assert_eq!((&a * &a).to_string(), "49");
+    assert_eq!((&a + &BigInt::from(7)), BigInt::zero());
+    assert!(!(&a + &BigInt::from(7)).is_negative());
+    assert_eq!(BigInt::from(-84).div_small(12), a);
+    assert_eq!("-123".parse::<BigInt>().unwrap(), BigInt::from(-123));
+    assert!(a < b);
+}
@@ -0,0 +1,239 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{BufRead, Write};
+
+use automaton::automaton::Automaton;
+use automaton::dfa::{Dfa, ToDfa, ToMinimalCompleteDfa};
+use automaton::dot::ToDot;
+use automaton::json::ToJson;
+use automaton::language::{CheckEquivalence, Equivalence};
+use automaton::nfa::Nfa;
+use automaton::parse::ParseError;
+use automaton::simplify::ToSimplifiedRegex;
+
+use crate::repl;
+
+pub const USAGE: &str = "\
+Usage: automaton <command> [options] <file>...
+
+Commands:
+    determinize <file>       subset construction
+    minimize <file>          minimal complete DFA
+    complement <file>        complement over the automaton's own alphabet
+                             or exactly the one given with --alphabet
+    to-regex <file>          equivalent regular expression
+    accept <file> [word]...  test words, read one per line from standard
+                             input when none are given
+    equiv <file> <file>      compare the languages of two automata
+    repl                     interactive session, type `help` for commands
+
+Options:
+    -f, --from <format>      input format: text, dot or json (default: by
+                             file extension, otherwise text)
+    -t, --to <format>        output format: text, dot or json (default: text)
+    -o, --output <file>      write to a file instead of standard output
+    -a, --alphabet <chars>   alphabet for complement
+    -h, --help               print this message
+
+A file name of - reads standard input, at most once. Words are printed
+quoted. `accept` and `equiv` exit with status 1 when a word is rejected
+or the automata differ.";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Dot,
+    Json,
+}
+
+impl Format {
+    fn parse(name: &str) -> Result<Format, String> {
+        match name {
+            "text" | "txt" => Ok(Format::Text),
+            "dot" | "gv" => Ok(Format::Dot),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format '{name}'")),
+        }
+    }
+
+    pub fn from_path(path: &str) -> Format {
+        match path.rsplit_once('.') {
+            Some((_, extension)) => Format::parse(extension).unwrap_or(Format::Text),
+            None => Format::Text,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Determinize,
+    Minimize,
+    Complement,
+    ToRegex,
+    Accept,
+    Equiv,
+    Repl,
+    Help,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub command: Command,
+    /// Input files followed by the words for `accept`.
+    pub arguments: Vec<String>,
+    pub from: Option<Format>,
+    pub to: Format,
+    pub output: Option<String>,
+    pub alphabet: Option<BTreeSet<char>>
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+    let mut args = args.into_iter();
+    let command = match args.next().as_deref() {
+        Some("determinize") => Command::Determinize,
+        Some("minimize") => Command::Minimize,
+        Some("complement") => Command::Complement,
+        Some("to-regex") => Command::ToRegex,
+        Some("accept") => Command::Accept,
+        Some("equiv") => Command::Equiv,
+        Some("repl") => Command::Repl,
+        Some("-h" | "--help" | "help") => Command::Help,
+        Some(name) => return Err(format!("unknown command '{name}'")),
+        None => return Err(String::from("missing command")),
+    };
+    let mut options = Options{command, arguments: vec![], from: None, to: Format::Text, output: None, alphabet: None};
+    let mut only_arguments = false;
+    while let Some(arg) = args.next() {
+        if only_arguments || !arg.starts_with('-') || arg == "-" {
+            options.arguments.push(arg);
+            continue;
+        }
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for {name}"));
+        match arg.as_str() {
+            "--" => only_arguments = true,
+            "-f" | "--from" => options.from = Some(Format::parse(&value(&arg)?)?),
+            "-t" | "--to" => options.to = Format::parse(&value(&arg)?)?,
+            "-o" | "--output" => options.output = Some(value(&arg)?),
+            "-a" | "--alphabet" => options.alphabet = Some(value(&arg)?.chars().collect()),
+            "-h" | "--help" => options.command = Command::Help,
+            _ => return Err(format!("unknown option '{arg}'")),
+        }
+    }
+
+    let files = match options.command {
+        Command::Help | Command::Repl => 0,
+        Command::Equiv => 2,
+        _ => 1,
+    };
+    if options.arguments.len() < files || (options.command != Command::Accept && options.arguments.len() > files) {
+        return Err(format!("expected {files} file argument(s)"));
+    }
+    let words_from_stdin = options.command == Command::Accept && options.arguments.len() == files;
+    if options.arguments[..files].iter().filter(|arg| *arg == "-").count() + usize::from(words_from_stdin) > 1 {
+        return Err(String::from("standard input cannot supply both the automaton and the words"));
+    }
+    Ok(options)
+}
+
+pub fn parse_automaton(src: &str, format: Format) -> Result<Nfa<String>, ParseError> {
+    match format {
+        Format::Text => src.parse(),
+        Format::Dot => Nfa::from_dot(src),
+        Format::Json => Nfa::<String>::from_json(src),
+    }
+}
+
+fn read_automaton<R: BufRead>(path: &str, from: Option<Format>, stdin: &mut R) -> Result<Nfa<String>, String> {
+    let src = if path == "-" {
+        let mut src = String::new();
+        stdin.read_to_string(&mut src).map_err(|error| format!("standard input: {error}"))?;
+        src
+    } else {
+        fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?
+    };
+    parse_automaton(&src, from.unwrap_or(Format::from_path(path))).map_err(|error| format!("{path}:{error}"))
+}
+
+pub fn write_nfa(nfa: &Nfa<String>, format: Format) -> String {
+    match format {
+        Format::Text => nfa.to_string(),
+        Format::Dot => nfa.to_dot(),
+        Format::Json => nfa.to_json(),
+    }
+}
+
+fn write_dfa(dfa: &Dfa, format: Format) -> String {
+    match format {
+        Format::Text => dfa.to_string(),
+        Format::Dot => dfa.to_dot(),
+        Format::Json => dfa.to_json(),
+    }
+}
+
+/// Simplified `to_regex` output, which has no expression for the empty
+/// language nor for symbols that are regex operators.
+pub fn regex_of(nfa: &Nfa<String>) -> Result<String, String> {
+    nfa.to_simplified_regex()
+        .ok_or_else(|| String::from("the language is empty or has a symbol '+', '*', '(' or ')' and has no regular expression"))
+}
+
+/// Runs a parsed command line. Returns whether the command succeeded in the
+/// sense of the exit status: every word accepted for `accept`, equal
+/// languages for `equiv`.
+pub fn run<R: BufRead, W: Write>(options: &Options, stdin: &mut R, stdout: &mut W) -> Result<bool, String> {
+    if options.command == Command::Help {
+        writeln!(stdout, "{USAGE}").map_err(|error| error.to_string())?;
+        return Ok(true);
+    }
+    if options.command == Command::Repl {
+        repl::run(stdin, stdout).map_err(|error| error.to_string())?;
+        return Ok(true);
+    }
+    let nfa = read_automaton(&options.arguments[0], options.from, stdin)?;
+    let mut success = true;
+    let output = match options.command {
+        Command::Determinize => write_dfa(&nfa.to_dfa(), options.to),
+        Command::Minimize => write_dfa(&nfa.to_mcdfa(), options.to),
+        Command::Complement => {
+            let dfa = nfa.to_dfa();
+            let alphabet = options.alphabet.clone().unwrap_or(dfa.get_alphabet());
+            write_dfa(&dfa.complement_over(&alphabet), options.to)
+        }
+        Command::ToRegex => format!("{}\n", regex_of(&nfa)?),
+        Command::Accept => {
+            let mut words: Vec<String> = options.arguments[1..].to_vec();
+            if words.is_empty() {
+                for line in stdin.lines() {
+                    words.push(line.map_err(|error| format!("standard input: {error}"))?);
+                }
+            }
+            let mut output = String::new();
+            for word in words {
+                let accepted = nfa.accept(&word);
+                success &= accepted;
+                output.push_str(&format!("{}\t{word:?}\n", if accepted { "accept" } else { "reject" }));
+            }
+            output
+        }
+        Command::Equiv => {
+            let other = read_automaton(&options.arguments[1], options.from, stdin)?;
+            match nfa.check_equivalence(&other) {
+                Equivalence::Equivalent => String::from("equivalent\n"),
+                Equivalence::Counterexample(word) => {
+                    success = false;
+                    let side = if nfa.accept(&word) { "first" } else { "second" };
+                    format!("not equivalent: {word:?} is accepted only by the {side}\n")
+                }
+            }
+        }
+        Command::Help | Command::Repl => unreachable!(),
+    };
+    match &options.output {
+        Some(path) => fs::write(path, output).map_err(|error| format!("{path}: {error}"))?,
+        None => stdout.write_all(output.as_bytes()).map_err(|error| error.to_string())?,
+    }
+    Ok(success)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,126 @@
+use std::env;
+use std::fs;
+use std::io::Cursor;
+
+use automaton::automaton::Automaton;
+use automaton::dfa::Dfa;
+
+use crate::cli::*;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+fn run_with(line: &str, stdin: &str) -> (Result<bool, String>, String) {
+    let options = parse_args(args(line)).unwrap();
+    let mut stdout = vec![];
+    let result = run(&options, &mut Cursor::new(stdin), &mut stdout);
+    (result, String::from_utf8(stdout).unwrap())
+}
+
+const NFA: &str = "\
+Starting state: 0
+0 -> 0 by \"a\"
+0 -> 0 by \"b\"
+0 -> 1 by \"ab\"
+Terminal states:
+1
+";
+
+#[test]
+fn parse_args_test() {
+    let options = parse_args(args("minimize -f dot --to json -o out.json in.gv")).unwrap();
+    assert_eq!(options.command, Command::Minimize);
+    assert_eq!(options.arguments, vec!["in.gv"]);
+    assert_eq!(options.from, Some(Format::Dot));
+    assert_eq!(options.to, Format::Json);
+    assert_eq!(options.output.as_deref(), Some("out.json"));
+
+    let options = parse_args(args("accept - -- -a b")).unwrap();
+    assert_eq!(options.arguments, vec!["-", "-a", "b"]);
+
+    assert_eq!(parse_args(args("reverse a")).err().unwrap(), "unknown command 'reverse'");
+    assert_eq!(parse_args(args("equiv a")).err().unwrap(), "expected 2 file argument(s)");
+    assert_eq!(parse_args(args("to-regex a -t svg")).err().unwrap(), "unknown format 'svg'");
+    assert_eq!(parse_args(args("to-regex a -o")).err().unwrap(), "missing value for -o");
+
+    let conflict = "standard input cannot supply both the automaton and the words";
+    assert_eq!(parse_args(args("accept -")).err().unwrap(), conflict);
+    assert_eq!(parse_args(args("equiv - -")).err().unwrap(), conflict);
+    assert!(parse_args(args("accept a")).is_ok());
+    assert!(parse_args(args("equiv a -")).is_ok());
+}
+
+#[test]
+fn transform_commands_test() {
+    let (result, output) = run_with("determinize -", NFA);
+    assert_eq!(result, Ok(true));
+    assert!(output.starts_with("Starting state: 0\n"));
+
+    let (_, output) = run_with("minimize - --to dot", NFA);
+    assert!(output.starts_with("digraph {\n"));
+
+    let (_, output) = run_with("complement - -a abc --to json", NFA);
+    assert!(output.contains("\"kind\": \"dfa\""));
+    assert!(output.contains("\"word\": \"c\""));
+
+    // Symbols missing from --alphabet are left out of the complement.
+    let (_, output) = run_with("complement - -a a", NFA);
+    assert!(!output.contains("by \"b\""));
+    let complement: Dfa = output.parse().unwrap();
+    assert!(complement.accept("aa"));
+    assert!(!complement.accept("ab"));
+    assert!(!complement.accept("b"));
+
+    let (_, output) = run_with("to-regex -", NFA);
+    assert!(output.ends_with('\n'));
+    assert!(!output.trim().is_empty());
+
+    // {ba, baa}
+    let dfa = "Starting state: 0\n0 -> 2 by \"b\"\n2 -> 3 by \"a\"\n3 -> 1 by \"a\"\nTerminal states:\n1\n3\n";
+    let (_, output) = run_with("to-regex -", dfa);
+    assert_eq!(output, "ba(+a)\n");
+
+    let (result, _) = run_with("to-regex -", "Starting state: 0\n0 -> 1 by \"a+b\"\nTerminal states:\n1\n");
+    assert!(result.err().unwrap().contains("has no regular expression"));
+
+    let (result, _) = run_with("to-regex -", "Starting state: 0\n0 -> 1 by \"a\"\nTerminal states:\n");
+    assert!(result.err().unwrap().contains("has no regular expression"));
+}
+
+#[test]
+fn accept_command_test() {
+    let (result, output) = run_with("accept - ab bab", NFA);
+    assert_eq!(result, Ok(true));
+    assert_eq!(output, "accept\t\"ab\"\naccept\t\"bab\"\n");
+
+    let (result, output) = run_with("accept - ba", NFA);
+    assert_eq!(result, Ok(false));
+    assert_eq!(output, "reject\t\"ba\"\n");
+}
+
+#[test]
+fn equiv_command_test() {
+    let dir = env::temp_dir().join(format!("automaton-cli-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let text = dir.join("nfa.txt");
+    let dot = dir.join("mcdfa.dot");
+    let other = dir.join("other.json");
+    fs::write(&text, NFA).unwrap();
+    run_with(&format!("minimize {} -t dot -o {}", text.display(), dot.display()), "").0.unwrap();
+    run_with(&format!("determinize {} -t json -o {}", dot.display(), other.display()), "").0.unwrap();
+
+    let (result, output) = run_with(&format!("equiv {} {}", text.display(), other.display()), "");
+    assert_eq!(result, Ok(true));
+    assert_eq!(output, "equivalent\n");
+
+    let (result, output) = run_with(&format!("equiv {} -", text.display()), "Starting state: 0\nTerminal states:\n0\n");
+    assert_eq!(result, Ok(false));
+    assert_eq!(output, "not equivalent: \"\" is accepted only by the second\n");
+
+    let (result, _) = run_with(&format!("to-regex {}", dir.join("missing.txt").display()), "");
+    assert!(result.err().unwrap().contains("missing.txt"));
+    let (result, _) = run_with("to-regex -", "Starting state: x\n");
+    assert_eq!(result.err().unwrap(), "-:1:17: expected state index");
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::fmt::Display;
+
+use crate::nfa::{Nfa, NfaState};
+use crate::state::Traversable;
+
+/// Labels that have an empty word, used to glue automata together.
+pub trait Epsilon {
+    fn epsilon() -> Self;
+}
+
+impl Epsilon for String {
+    fn epsilon() -> Self {
+        String::new()
+    }
+}
+
+/// Copies the states of `nfa` into `result`, numbered from `result`'s next
+/// free index on, and returns the new indices of its starting state and
+/// terminal states. Transitions to missing states are dropped.
+fn append<T: Traversable + Display + Clone>(result: &mut Nfa<T>, nfa: &Nfa<T>) -> (usize, Vec<usize>) {
+    let offset = next_index(result);
+    let mut index: BTreeMap<usize, usize> = nfa.states.keys().enumerate().map(|(i, idx)| (*idx, offset + i)).collect();
+    for (idx, state) in nfa.states.iter() {
+        let mut copy = NfaState::new(index[idx], state.is_terminal);
+        for (word, next_state) in state.transitions.iter() {
+            if let Some(next) = index.get(next_state) {
+                copy.add_transition(word.clone(), *next);
+            }
+        }
+        result.add_state(copy);
+    }
+    if let Entry::Vacant(entry) = index.entry(nfa.starting_state) {
+        let start = next_index(result);
+        result.add_state(NfaState::new(start, false));
+        entry.insert(start);
+    }
+    let terminals = nfa.states.values().filter(|state| state.is_terminal).map(|state| index[&state.index]).collect();
+    (index[&nfa.starting_state], terminals)
+}
+
+fn next_index<T: Traversable + Display + Clone>(nfa: &Nfa<T>) -> usize {
+    nfa.states.keys().next_back().map_or(0, |idx| idx + 1)
+}
+
+/// Thompson-style composition. Each operation renumbers the states of its
+/// operands, the first one from 0, and connects them with empty-word
+/// transitions, so the operands may use overlapping indices.
+impl<T: Traversable + Display + Clone + Epsilon> Nfa<T> {
+    /// Copy of `self` behind a fresh starting state with an empty-word
+    /// transition to the old one, and the new indices of the terminal states.
+    fn with_new_start(&self, is_terminal: bool) -> (Nfa<T>, Vec<usize>) {
+        let mut result = Nfa::new(0);
+        let (start, terminals) = append(&mut result, self);
+        let new_start = next_index(&result);
+        let mut state = NfaState::new(new_start, is_terminal);
+        state.add_transition(T::epsilon(), start);
+        result.add_state(state);
+        result.starting_state = new_start;
+        (result, terminals)
+    }
+
+    /// Accepts the words of either automaton.
+    pub fn union(&self, other: &Nfa<T>) -> Nfa<T> {
+        let mut result = Nfa::new(0);
+        let (left, _) = append(&mut result, self);
+        let (right, _) = append(&mut result, other);
+        let start = next_index(&result);
+        let mut state = NfaState::new(start, false);
+        state.add_transition(T::epsilon(), left);
+        state.add_transition(T::epsilon(), right);
+        result.add_state(state);
+        result.starting_state = start;
+        result
+    }
+
+    /// Accepts a word of `self` followed by a word of `other`.
+    pub fn concat(&self, other: &Nfa<T>) -> Nfa<T> {
+        let mut result = Nfa::new(0);
+        let (start, terminals) = append(&mut result, self);
+        let (right, _) = append(&mut result, other);
+        for idx in terminals {
+            let state = result.states.get_mut(&idx).unwrap();
+            state.is_terminal = false;
+            state.add_transition(T::epsilon(), right);
+        }
+        result.starting_state = start;
+        result
+    }
+
+    /// Accepts any number of words of `self`, including none.
+    pub fn star(&self) -> Nfa<T> {
+        let (mut result, terminals) = self.with_new_start(true);
+        let new_start = result.starting_state;
+        for idx in terminals {
+            result.states.get_mut(&idx).unwrap().add_transition(T::epsilon(), new_start);
+        }
+        result
+    }
+
+    /// Accepts one or more words of `self`.
+    pub fn plus(&self) -> Nfa<T> {
+        let mut result = Nfa::new(0);
+        let (start, terminals) = append(&mut result, self);
+        for idx in terminals {
+            result.states.get_mut(&idx).unwrap().add_transition(T::epsilon(), start);
+        }
+        result.starting_state = start;
+        result
+    }
+
+    /// Accepts the words of `self` and the empty word.
+    pub fn optional(&self) -> Nfa<T> {
+        self.with_new_start(true).0
+    }
+}
+
+#[cfg(test)]
+mod tests;
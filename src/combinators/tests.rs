@@ -0,0 +1,56 @@
+use crate::automaton::*;
+use crate::language::*;
+use crate::nfa::*;
+use crate::testing::*;
+
+fn word(word: &str) -> Nfa<String> {
+    let mut q0: NfaState<String> = NfaState::new(0, false);
+    q0.add_transition(String::from(word), 1);
+    let mut nfa: Nfa<String> = Nfa::new(0);
+    nfa.add_state(q0);
+    nfa.add_state(NfaState::new(1, true));
+    nfa
+}
+
+fn assert_equivalent(left: &Nfa<String>, right: &Nfa<String>) {
+    assert!(matches!(left.check_equivalence(right), Equivalence::Equivalent), "{left}");
+}
+
+#[test]
+fn combinators_test() {
+    let (a, bc) = (word("a"), word("bc"));
+    assert_equivalent(&a.union(&bc), &from_regex("a+bc"));
+    assert_equivalent(&a.concat(&bc), &from_regex("abc"));
+    assert_equivalent(&bc.star(), &from_regex("(bc)*"));
+    assert_equivalent(&bc.plus(), &from_regex("bc(bc)*"));
+    assert_equivalent(&a.optional(), &from_regex("a+"));
+    assert_equivalent(&a.union(&bc).star().concat(&a.plus()), &from_regex("(a+bc)*aa*"));
+
+    let union = a.union(&bc);
+    assert_eq!(union.states.len(), 5);
+    assert_eq!(union.starting_state, 4);
+    assert!(union.validate().is_ok());
+}
+
+#[test]
+fn combinators_renumber_test() {
+    // Operands with the same and with sparse indices.
+    let mut q5: NfaState<String> = NfaState::new(5, false);
+    q5.add_transition(String::from("x"), 9);
+    let mut q9: NfaState<String> = NfaState::new(9, true);
+    q9.add_transition(String::from("y"), 5);
+    let mut sparse: Nfa<String> = Nfa::new(5);
+    sparse.add_state(q5);
+    sparse.add_state(q9);
+
+    let nfa = sparse.concat(&sparse).union(&word("")).plus();
+    assert!(nfa.validate().is_ok());
+    assert_eq!(nfa.states.keys().copied().collect::<Vec<_>>(), (0..7).collect::<Vec<_>>());
+    assert_equivalent(&nfa, &from_regex("(x(yx)*x(yx)*+)(x(yx)*x(yx)*+)*"));
+    assert!(nfa.accept("xyxxxx"));
+    assert!(!nfa.accept("xy"));
+
+    let empty: Nfa<String> = Nfa::new(3);
+    assert!(empty.star().accept(""));
+    assert!(!empty.concat(&word("a")).accept("a"));
+}
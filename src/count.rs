@@ -0,0 +1,170 @@
+use crate::bigint::{BigInt, BigUint};
+use crate::dfa::{Dfa, ToMinimalCompleteDfa, Trimmed};
+
+/// Linear recurrence with constant coefficients satisfied by the number of
+/// accepted words of each length:
+/// `c(n) = coefficients[0]·c(n-1) + ... + coefficients[d-1]·c(n-d)` for
+/// `n ≥ d`, starting from `initial = [c(0), ..., c(d-1)]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearRecurrence {
+    pub coefficients: Vec<BigInt>,
+    pub initial: Vec<BigUint>
+}
+
+impl LinearRecurrence {
+    pub fn order(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    pub fn nth(&self, n: usize) -> BigUint {
+        if self.order() == 0 {
+            return BigUint::zero();
+        }
+        if n < self.initial.len() {
+            return self.initial[n].clone();
+        }
+        let mut values: Vec<BigInt> = self.initial.iter().cloned().map(BigInt::from).collect();
+        for _ in self.order()..=n {
+            let len = values.len();
+            let mut next = BigInt::zero();
+            for (i, coefficient) in self.coefficients.iter().enumerate() {
+                next = &next + &(coefficient * &values[len - 1 - i]);
+            }
+            values.remove(0);
+            values.push(next);
+        }
+        values.pop().and_then(|value| value.to_biguint()).unwrap_or_default()
+    }
+
+    /// Rational generating function `Σ c(n)·xⁿ = P(x) / Q(x)` as the
+    /// coefficient lists `(P, Q)`, lowest degree first. The poles of `Q`
+    /// closest to zero determine the asymptotic growth of `c(n)`.
+    pub fn generating_function(&self) -> (Vec<BigInt>, Vec<BigInt>) {
+        let mut denominator = vec![BigInt::from(1)];
+        denominator.extend(self.coefficients.iter().map(|coefficient| -coefficient));
+        let mut numerator = vec![];
+        for j in 0..self.order() {
+            let mut sum = BigInt::zero();
+            for (q, c) in denominator.iter().zip(self.initial[..=j].iter().rev()) {
+                sum = &sum + &(q * &BigInt::from(c.clone()));
+            }
+            numerator.push(sum);
+        }
+        while numerator.last().is_some_and(BigInt::is_zero) {
+            numerator.pop();
+        }
+        (numerator, denominator)
+    }
+}
+
+/// `counts[r][s]` is the number of words of length `r` that lead from state
+/// `s` of `trimmed` to a terminal state, for `r` up to `len`.
+pub(crate) fn suffix_counts(trimmed: &Trimmed, len: usize) -> Vec<Vec<BigUint>> {
+    let mut counts: Vec<Vec<BigUint>> = vec![trimmed.is_terminal.iter()
+        .map(|is_terminal| if *is_terminal { BigUint::one() } else { BigUint::zero() })
+        .collect()];
+    for r in 1..=len {
+        let next = trimmed.transitions.iter()
+            .map(|edges| {
+                let mut sum = BigUint::zero();
+                for (_, next_state) in edges {
+                    sum += &counts[r - 1][*next_state];
+                }
+                sum
+            })
+            .collect();
+        counts.push(next);
+    }
+    counts
+}
+
+impl Dfa {
+    /// Number of accepted words of exactly `len` symbols.
+    pub fn count_words(&self, len: usize) -> BigUint {
+        let trimmed = self.trimmed();
+        match trimmed.starting_state {
+            Some(start) => suffix_counts(&trimmed, len)[len][start].clone(),
+            None => BigUint::zero(),
+        }
+    }
+
+    /// Total number of accepted words, or `None` when the language is
+    /// infinite.
+    pub fn count_all_words(&self) -> Option<BigUint> {
+        let trimmed = self.trimmed();
+        let Some(start) = trimmed.starting_state else {
+            return Some(BigUint::zero());
+        };
+        // Every state is useful, so the language is finite exactly when no
+        // path is as long as the number of states.
+        let n = trimmed.is_terminal.len();
+        let counts = suffix_counts(&trimmed, n);
+        if counts[n].iter().any(|count| !count.is_zero()) {
+            return None;
+        }
+        let mut total = BigUint::zero();
+        for count in counts.iter() {
+            total += &count[start];
+        }
+        Some(total)
+    }
+
+    /// Recurrence derived from the characteristic polynomial of the
+    /// transition matrix of the minimal automaton (Cayley–Hamilton). Its
+    /// order is the number of useful states, which need not be the smallest
+    /// possible order.
+    pub fn recurrence(&self) -> LinearRecurrence {
+        let trimmed = self.to_mcdfa().trimmed();
+        let Some(start) = trimmed.starting_state else {
+            return LinearRecurrence{coefficients: vec![], initial: vec![]};
+        };
+        let d = trimmed.is_terminal.len();
+
+        // Sparse rows of the transition matrix: `matrix[s]` lists the targets
+        // of `s` with the number of symbols leading there.
+        let mut matrix: Vec<Vec<(usize, i64)>> = vec![vec![]; d];
+        for (s, edges) in trimmed.transitions.iter().enumerate() {
+            for (_, next_state) in edges {
+                match matrix[s].iter_mut().find(|(t, _)| t == next_state) {
+                    Some((_, weight)) => *weight += 1,
+                    None => matrix[s].push((*next_state, 1)),
+                }
+            }
+        }
+
+        // Faddeev–LeVerrier: M(k) = A·M(k-1) + p(d-k+1)·I and
+        // p(d-k) = -tr(A·M(k)) / k, where p is the characteristic polynomial.
+        let mut polynomial = vec![BigInt::zero(); d + 1];
+        polynomial[d] = BigInt::from(1);
+        let mut m: Vec<Vec<BigInt>> = vec![vec![BigInt::zero(); d]; d];
+        for k in 1..=d {
+            let mut next: Vec<Vec<BigInt>> = vec![vec![BigInt::zero(); d]; d];
+            for (i, row) in matrix.iter().enumerate() {
+                for (l, weight) in row {
+                    let weight = BigInt::from(*weight);
+                    for j in 0..d {
+                        next[i][j] = &next[i][j] + &(&weight * &m[*l][j]);
+                    }
+                }
+                next[i][i] = &next[i][i] + &polynomial[d - k + 1];
+            }
+            let mut trace = BigInt::zero();
+            for (i, row) in matrix.iter().enumerate() {
+                for (l, weight) in row {
+                    trace = &trace + &(&BigInt::from(*weight) * &next[*l][i]);
+                }
+            }
+            polynomial[d - k] = -&trace.div_small(k as i64);
+            m = next;
+        }
+
+        let counts = suffix_counts(&trimmed, d - 1);
+        LinearRecurrence{
+            coefficients: (1..=d).map(|i| -&polynomial[d - i]).collect(),
+            initial: counts.iter().map(|count| count[start].clone()).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
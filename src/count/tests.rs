@@ -0,0 +1,52 @@
+use crate::bigint::*;
+use crate::dfa::*;
+use crate::testing::*;
+
+#[test]
+fn count_words_test() {
+    let all = dfa_from_regex("(a+b)*");
+    assert_eq!(all.count_words(0), BigUint::one());
+    assert_eq!(all.count_words(100).to_string(), "1267650600228229401496703205376");
+
+    // Words without "bb" are counted by the Fibonacci numbers.
+    let no_bb = dfa_from_regex("(a+ba)*(+b)");
+    assert_eq!(no_bb.count_words(10), BigUint::from(144));
+    assert_eq!(no_bb.count_words(90).to_string(), "7540113804746346429");
+
+    assert_eq!(dfa_from_regex("ab+ba").count_words(1), BigUint::zero());
+}
+
+#[test]
+fn count_all_words_test() {
+    assert_eq!(dfa_from_regex("ab+ba+").count_all_words(), Some(BigUint::from(3)));
+    assert_eq!(dfa_from_regex("(a+b)(a+b+c)(a+b)").count_all_words(), Some(BigUint::from(12)));
+    assert_eq!(dfa_from_regex("a(b)*").count_all_words(), None);
+
+    let mut q0 = DfaState::new(0, false);
+    q0.add_transition('a', 0);
+    let mut empty = Dfa::new(0);
+    empty.add_state(q0);
+    assert_eq!(empty.count_all_words(), Some(BigUint::zero()));
+    assert_eq!(empty.recurrence().nth(5), BigUint::zero());
+}
+
+#[test]
+fn recurrence_test() {
+    let all = dfa_from_regex("(a+b)*").recurrence();
+    assert_eq!(all.coefficients, vec![BigInt::from(2)]);
+    assert_eq!(all.initial, vec![BigUint::one()]);
+    assert_eq!(all.generating_function(), (vec![BigInt::from(1)], vec![BigInt::from(1), BigInt::from(-2)]));
+
+    let no_bb = dfa_from_regex("(a+ba)*(+b)");
+    let recurrence = no_bb.recurrence();
+    assert_eq!(recurrence.coefficients, vec![BigInt::from(1), BigInt::from(1)]);
+    for n in [0, 1, 2, 7, 60] {
+        assert_eq!(recurrence.nth(n), no_bb.count_words(n));
+    }
+
+    let even_a = dfa_from_regex("(b+ab*a)*c");
+    let recurrence = even_a.recurrence();
+    for n in 0..12 {
+        assert_eq!(recurrence.nth(n), even_a.count_words(n));
+    }
+}
@@ -1,6 +1,10 @@
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::collections::hash_map::Entry;
 use std::fmt;
 
+use crate::error::AutomatonError;
+use crate::text::escape;
+
 use crate::automaton::{Automaton, ToRegex};
 use crate::nfa::*;
 
@@ -8,19 +12,95 @@ use crate::nfa::*;
 pub struct DfaState {
     pub index: usize,
     pub is_terminal: bool,
-    transitions: Vec<(char, usize)>
+    /// Private so that every change goes through `add_transition` or
+    /// `retain_transitions`, which keep `lookup` in sync.
+    transitions: Vec<(char, usize)>,
+    /// First target of every symbol in `transitions`.
+    lookup: HashMap<char, usize>
 }
 
 impl DfaState {
     pub fn new(index: usize, is_terminal: bool) -> Self {
-        Self{index: index, is_terminal: is_terminal, transitions: Vec::new()}
+        Self{index, is_terminal, transitions: Vec::new(), lookup: HashMap::new()}
     }
 
     pub fn add_transition(&mut self, sym: char, next_state: usize) {
         self.transitions.push((sym, next_state));
+        self.lookup.entry(sym).or_insert(next_state);
+    }
+
+    pub fn next_state(&self, sym: char) -> Option<usize> {
+        self.lookup.get(&sym).copied()
+    }
+
+    pub fn transitions(&self) -> &[(char, usize)] {
+        &self.transitions
+    }
+
+    /// Drops the transitions whose symbol fails `keep`.
+    pub fn retain_transitions<F: Fn(char) -> bool>(&mut self, keep: F) {
+        self.transitions.retain(|(ch, _)| keep(*ch));
+        self.lookup.retain(|ch, _| keep(*ch));
+    }
+}
+
+/// Collects states and transitions in any order and checks them in `build`.
+#[derive(Clone)]
+pub struct DfaBuilder {
+    starting_state: Option<usize>,
+    states: Vec<(usize, bool)>,
+    transitions: Vec<(usize, char, usize)>
+}
+
+impl DfaBuilder {
+    pub fn start(mut self, idx: usize) -> Self {
+        self.starting_state = Some(idx);
+        self
+    }
+
+    pub fn state(mut self, idx: usize, is_terminal: bool) -> Self {
+        self.states.push((idx, is_terminal));
+        self
+    }
+
+    pub fn transition(mut self, from: usize, sym: char, to: usize) -> Self {
+        self.transitions.push((from, sym, to));
+        self
+    }
+
+    pub fn build(self) -> Result<Dfa, AutomatonError> {
+        let starting_state = self.starting_state.ok_or(AutomatonError::MissingStartState)?;
+        let mut dfa = Dfa::new(starting_state);
+        for (idx, is_terminal) in self.states {
+            dfa.try_add_state(DfaState::new(idx, is_terminal))?;
+        }
+        if !dfa.states.contains_key(&starting_state) {
+            return Err(AutomatonError::UnknownStartState(starting_state));
+        }
+        for (from, sym, to) in self.transitions {
+            if !dfa.states.contains_key(&to) {
+                return Err(AutomatonError::DanglingTransition{from, to});
+            }
+            let Some(state) = dfa.states.get_mut(&from) else {
+                return Err(AutomatonError::DanglingTransition{from, to});
+            };
+            if state.next_state(sym).is_some() {
+                return Err(AutomatonError::NondeterministicTransition{state: from, symbol: sym});
+            }
+            state.add_transition(sym, to);
+        }
+        Ok(dfa)
     }
 }
 
+/// Dense copy of the states of a `Dfa` that are reachable and can reach a
+/// terminal state. `starting_state` is `None` when the language is empty.
+pub(crate) struct Trimmed {
+    pub starting_state: Option<usize>,
+    pub is_terminal: Vec<bool>,
+    pub transitions: Vec<Vec<(char, usize)>>
+}
+
 #[derive(Clone)]
 pub struct Dfa {
     pub starting_state: usize,
@@ -29,9 +109,14 @@ pub struct Dfa {
 
 impl Dfa {
     pub fn new(starting_state: usize) -> Self {
-        Self{starting_state: starting_state, states: BTreeMap::new()}
+        Self{starting_state, states: BTreeMap::new()}
     }
     
+    pub fn builder() -> DfaBuilder {
+        DfaBuilder{starting_state: None, states: vec![], transitions: vec![]}
+    }
+
+    /// Panics when a state with the same index exists, see `try_add_state`.
     pub fn add_state(&mut self, state: DfaState) {
         if self.states.contains_key(&state.index) {
             panic!("State index duplicate!");
@@ -39,6 +124,34 @@ impl Dfa {
         self.states.insert(state.index, state);
     }
 
+    pub fn try_add_state(&mut self, state: DfaState) -> Result<(), AutomatonError> {
+        if self.states.contains_key(&state.index) {
+            return Err(AutomatonError::DuplicateState(state.index));
+        }
+        self.states.insert(state.index, state);
+        Ok(())
+    }
+
+    /// Checks that the starting state and the ends of every transition exist
+    /// and that no state has two transitions on the same symbol.
+    pub fn validate(&self) -> Result<(), AutomatonError> {
+        if !self.states.contains_key(&self.starting_state) {
+            return Err(AutomatonError::UnknownStartState(self.starting_state));
+        }
+        for state in self.states.values() {
+            for (i, (ch, next_state)) in state.transitions.iter().enumerate() {
+                if !self.states.contains_key(next_state) {
+                    return Err(AutomatonError::DanglingTransition{from: state.index, to: *next_state});
+                }
+                if state.transitions[..i].iter().any(|(other, _)| other == ch) {
+                    return Err(AutomatonError::NondeterministicTransition{state: state.index, symbol: *ch});
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::for_kv_map)]
     pub fn get_alphabet(&self) -> BTreeSet<char> {
         let mut alphabet: BTreeSet<char> = BTreeSet::new();
         for (_, state) in self.states.iter() {
@@ -51,22 +164,25 @@ impl Dfa {
 }
 
 impl Automaton<'_> for Dfa {
-    fn accept<'a>(&self, str: &'a str) -> bool {
-        let mut cur = self.starting_state;
-        for i in 0..str.len() {
-            let mut success = false;
-            for (ch, next_state) in self.states[&cur].transitions.iter() {
-                if *ch == str.chars().nth(i).unwrap() {
-                    cur = *next_state;
-                    success = true;
-                    break;
-                }
-            }
-            if !success {
-                return false
+    fn accept(&self, str: &str) -> bool {
+        self.accept_chars(str.chars())
+    }
+}
+
+impl Dfa {
+    /// Runs the automaton over `input` one symbol at a time, taking constant
+    /// time per symbol. Missing transitions and states reject.
+    pub fn accept_chars<I: IntoIterator<Item = char>>(&self, input: I) -> bool {
+        let Some(mut state) = self.states.get(&self.starting_state) else {
+            return false;
+        };
+        for ch in input {
+            match state.next_state(ch).and_then(|next_state| self.states.get(&next_state)) {
+                Some(next) => state = next,
+                None => return false,
             }
         }
-        self.states[&cur].is_terminal
+        state.is_terminal
     }
 }
 
@@ -76,20 +192,27 @@ pub trait ToCompleteDfa {
 
 impl ToCompleteDfa for Dfa {
     fn to_cdfa(&self) -> Dfa {
+        self.to_cdfa_over(&self.get_alphabet())
+    }
+}
+
+impl Dfa {
+    /// Completes the automaton over `alphabet` by sending every missing
+    /// transition to a fresh non-terminal sink state.
+    pub fn to_cdfa_over(&self, alphabet: &BTreeSet<char>) -> Dfa {
         let mut cdfa = self.clone();
         let mut needed = false;
-        let alphabet = self.get_alphabet();
-        let virtual_index = self.states.keys().next_back().unwrap() + 1;
+        let virtual_index = self.states.keys().next_back().map_or(0, |idx| idx + 1);
         let mut virtual_state = DfaState::new(virtual_index, false);
-        for ch in &alphabet {
+        for ch in alphabet {
             virtual_state.add_transition(*ch, virtual_index);
         }
-        for (_, state) in cdfa.states.iter_mut() {
+        for state in cdfa.states.values_mut() {
             let mut exist: BTreeSet<char> = BTreeSet::new();
             for (ch, _) in state.transitions.iter() {
                 exist.insert(*ch);
             }
-            for ch in &alphabet {
+            for ch in alphabet {
                 if !exist.contains(ch) {
                     needed = true;
                     state.add_transition(*ch, virtual_index);
@@ -101,125 +224,397 @@ impl ToCompleteDfa for Dfa {
         }
         cdfa
     }
-}
 
-pub trait ToMinimalCompleteDfa {
-    fn to_mcdfa(&self) -> Dfa;
-}
+    /// Product construction over the union of both alphabets. A pair of
+    /// states is terminal when `op` holds for their terminal flags; only the
+    /// pairs reachable from the pair of starting states are built.
+    pub fn product<F: Fn(bool, bool) -> bool>(&self, other: &Dfa, op: F) -> Dfa {
+        let alphabet: BTreeSet<char> = self.get_alphabet().union(&other.get_alphabet()).copied().collect();
+        let left = self.to_cdfa_over(&alphabet);
+        let right = other.to_cdfa_over(&alphabet);
 
-impl ToMinimalCompleteDfa for Dfa {
-    fn to_mcdfa(&self) -> Dfa {
-        let cdfa = self.to_cdfa();
-        let n: usize = *cdfa.states.keys().next_back().unwrap() as usize + 1;
-        let mut reachable: Vec<bool> = vec![false; n];
-        let mut edge: Vec<Vec<char>> = vec![vec!['\0'; n]; n];
-        let mut is_terminal: Vec<bool> = vec![false; n];
-        let mut marked: Vec<Vec<bool>> = vec![vec![false; n]; n];
-
-        let mut queue: VecDeque<usize> = VecDeque::new();
-        queue.push_back(cdfa.starting_state as usize);
-        while !queue.is_empty() {
-            let cur = queue.pop_front().unwrap();
-            if reachable[cur] {
-                continue;
+        let mut pairs: Vec<(usize, usize)> = vec![(left.starting_state, right.starting_state)];
+        let mut index: HashMap<(usize, usize), usize> = HashMap::new();
+        index.insert(pairs[0], 0);
+        let mut product = Dfa::new(0);
+        let mut cur = 0;
+        while cur < pairs.len() {
+            let (l, r) = pairs[cur];
+            let is_terminal = op(left.states[&l].is_terminal, right.states[&r].is_terminal);
+            let mut state = DfaState::new(cur, is_terminal);
+            for ch in alphabet.iter() {
+                let pair = (left.states[&l].next_state(*ch).unwrap(),
+                            right.states[&r].next_state(*ch).unwrap());
+                let next = *index.entry(pair).or_insert_with(|| {
+                    pairs.push(pair);
+                    pairs.len() - 1
+                });
+                state.add_transition(*ch, next);
             }
-            reachable[cur] = true;
-            is_terminal[cur] = cdfa.states[&cur].is_terminal;
-            for (ch, next_state) in cdfa.states[&cur].transitions.clone() {
-                edge[cur][next_state as usize] = ch;
-                if !reachable[next_state as usize] {
-                    queue.push_back(next_state as usize);
+            product.add_state(state);
+            cur += 1;
+        }
+        product
+    }
+
+    pub fn intersection(&self, other: &Dfa) -> Dfa {
+        self.product(other, |a, b| a && b)
+    }
+
+    pub fn union(&self, other: &Dfa) -> Dfa {
+        self.product(other, |a, b| a || b)
+    }
+
+    pub fn difference(&self, other: &Dfa) -> Dfa {
+        self.product(other, |a, b| a && !b)
+    }
+
+    pub fn symmetric_difference(&self, other: &Dfa) -> Dfa {
+        self.product(other, |a, b| a != b)
+    }
+
+    /// Transitions between states that are both reachable from the starting
+    /// state and can reach a terminal state, sorted by symbol.
+    pub(crate) fn useful_transitions(&self) -> BTreeMap<usize, Vec<(char, usize)>> {
+        let mut reachable: BTreeSet<usize> = BTreeSet::from([self.starting_state]);
+        let mut reverse: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        let mut queue: VecDeque<usize> = VecDeque::from([self.starting_state]);
+        while let Some(cur) = queue.pop_front() {
+            for (_, next_state) in self.states[&cur].transitions.iter() {
+                reverse.entry(*next_state).or_default().push(cur);
+                if reachable.insert(*next_state) {
+                    queue.push_back(*next_state);
                 }
             }
         }
 
-        let mut pair_queue: VecDeque<(usize, usize)> = VecDeque::new();
-        for i in 0..n {
-            if !reachable[i] {
-                continue;
+        let mut useful: BTreeSet<usize> = BTreeSet::new();
+        for idx in reachable.iter() {
+            if self.states[idx].is_terminal {
+                useful.insert(*idx);
+                queue.push_back(*idx);
             }
-            for j in 0..n {
-                if !reachable[j] {
-                    continue;
-                }
-                if !marked[i][j] && is_terminal[i] != is_terminal[j] {
-                    marked[i][j] = true;
-                    marked[j][i] = true;
-                    pair_queue.push_back((i, j));
+        }
+        while let Some(cur) = queue.pop_front() {
+            for prev in reverse.get(&cur).into_iter().flatten() {
+                if useful.insert(*prev) {
+                    queue.push_back(*prev);
                 }
             }
         }
 
-        while !pair_queue.is_empty() {
-            let (u, v) = pair_queue.pop_front().unwrap();
-            for i in 0..n {
-                if !reachable[i] || edge[i][u] == '\0' {
-                    continue;
+        let mut edges: BTreeMap<usize, Vec<(char, usize)>> = BTreeMap::new();
+        for idx in useful.iter() {
+            let mut transitions: Vec<(char, usize)> = self.states[idx].transitions.iter()
+                .filter(|(_, next_state)| useful.contains(next_state))
+                .copied()
+                .collect();
+            transitions.sort();
+            edges.insert(*idx, transitions);
+        }
+        edges
+    }
+
+    /// The useful part of the automaton with states renumbered `0..n` in
+    /// increasing order of their original indices.
+    pub(crate) fn trimmed(&self) -> Trimmed {
+        let useful = self.useful_transitions();
+        let index: BTreeMap<usize, usize> = useful.keys().enumerate().map(|(i, idx)| (*idx, i)).collect();
+        Trimmed{
+            starting_state: index.get(&self.starting_state).copied(),
+            is_terminal: useful.keys().map(|idx| self.states[idx].is_terminal).collect(),
+            transitions: useful.values()
+                .map(|edges| edges.iter().map(|(ch, next_state)| (*ch, index[next_state])).collect())
+                .collect()
+        }
+    }
+
+    /// Breadth-first search with transitions taken in symbol order, so the
+    /// word found is the first accepted one in shortlex order.
+    pub fn shortest_accepted(&self) -> Option<String> {
+        let mut parent: HashMap<usize, Option<(usize, char)>> = HashMap::new();
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        parent.insert(self.starting_state, None);
+        queue.push_back(self.starting_state);
+        while let Some(cur) = queue.pop_front() {
+            if self.states[&cur].is_terminal {
+                let mut word = vec![];
+                let mut node = cur;
+                while let Some((prev, ch)) = parent[&node] {
+                    word.push(ch);
+                    node = prev;
                 }
-                for j in 0..n {
-                    if !reachable[j] || edge[j][v] == '\0' {
-                        continue;
-                    }
+                return Some(word.into_iter().rev().collect());
+            }
+            let mut transitions = self.states[&cur].transitions.clone();
+            transitions.sort();
+            for (ch, next_state) in transitions {
+                if let Entry::Vacant(entry) = parent.entry(next_state) {
+                    entry.insert(Some((cur, ch)));
+                    queue.push_back(next_state);
+                }
+            }
+        }
+        None
+    }
 
-                    if edge[i][u] == edge[j][v] && !marked[i][j] {
-                        marked[i][j] = true;
-                        marked[j][i] = true;
-                        pair_queue.push_back((i, j));
-                    }
+    /// First word over `alphabet` in shortlex order that is rejected.
+    pub fn shortest_rejected(&self, alphabet: &BTreeSet<char>) -> Option<String> {
+        let mut rejecting = self.to_cdfa_over(&self.get_alphabet().union(alphabet).copied().collect());
+        for state in rejecting.states.values_mut() {
+            state.is_terminal = !state.is_terminal;
+            state.retain_transitions(|ch| alphabet.contains(&ch));
+        }
+        rejecting.shortest_accepted()
+    }
+
+    /// Longest accepted word, the first in lexicographic order among those
+    /// of that length. `None` when the language is empty or infinite.
+    pub fn longest_accepted(&self) -> Option<String> {
+        let trimmed = self.trimmed();
+        let start = trimmed.starting_state?;
+        let n = trimmed.is_terminal.len();
+
+        // Kahn's algorithm; a cycle among useful states leaves some state
+        // out of the order and makes the language infinite.
+        let mut indegree = vec![0; n];
+        for edges in trimmed.transitions.iter() {
+            for (_, next_state) in edges {
+                indegree[*next_state] += 1;
+            }
+        }
+        let mut order: Vec<usize> = (0..n).filter(|s| indegree[*s] == 0).collect();
+        let mut cur = 0;
+        while cur < order.len() {
+            for (_, next_state) in trimmed.transitions[order[cur]].iter() {
+                indegree[*next_state] -= 1;
+                if indegree[*next_state] == 0 {
+                    order.push(*next_state);
                 }
             }
+            cur += 1;
+        }
+        if order.len() < n {
+            return None;
         }
 
-        
-        let mut component: Vec<i32> = vec![-1; n];
-        for i in 0..n {
-            if !marked[cdfa.starting_state as usize][i] {
-                component[i] = 0
+        let mut longest: Vec<usize> = vec![0; n];
+        for s in order.iter().rev() {
+            for (_, next_state) in trimmed.transitions[*s].iter() {
+                longest[*s] = longest[*s].max(longest[*next_state] + 1);
             }
         }
-        let mut component_count = 0;
-        for i  in 0..n {
-            if !reachable[i] {
+        let mut word = String::new();
+        let mut cur = start;
+        while longest[cur] > 0 {
+            let (ch, next_state) = trimmed.transitions[cur].iter()
+                .find(|(_, next_state)| longest[*next_state] + 1 == longest[cur])
+                .unwrap();
+            word.push(*ch);
+            cur = *next_state;
+        }
+        Some(word)
+    }
+}
+
+pub trait ToMinimalCompleteDfa {
+    fn to_mcdfa(&self) -> Dfa;
+}
+
+/// Refinable partition of `0..n` in the style of Valmari and Lehtinen:
+/// the members of every block are kept contiguous in `elements`, and the
+/// marked members of a block are moved to its front.
+struct Partition {
+    elements: Vec<usize>,
+    location: Vec<usize>,
+    block: Vec<usize>,
+    first: Vec<usize>,
+    mid: Vec<usize>,
+    end: Vec<usize>,
+    touched: Vec<usize>
+}
+
+impl Partition {
+    fn new(is_terminal: &[bool]) -> Self {
+        let mut elements: Vec<usize> = (0..is_terminal.len()).collect();
+        elements.sort_by_key(|e| is_terminal[*e]);
+        let mut location = vec![0; elements.len()];
+        for (i, e) in elements.iter().enumerate() {
+            location[*e] = i;
+        }
+        let mut partition = Self{elements, location, block: vec![0; is_terminal.len()],
+                                 first: vec![0], mid: vec![0], end: vec![is_terminal.len()],
+                                 touched: vec![]};
+        let split_at = is_terminal.iter().filter(|t| !**t).count();
+        if split_at > 0 && split_at < is_terminal.len() {
+            partition.end[0] = split_at;
+            partition.first.push(split_at);
+            partition.mid.push(split_at);
+            partition.end.push(is_terminal.len());
+            for i in split_at..is_terminal.len() {
+                partition.block[partition.elements[i]] = 1;
+            }
+        }
+        partition
+    }
+
+    fn len(&self) -> usize {
+        self.first.len()
+    }
+
+    fn size(&self, block: usize) -> usize {
+        self.end[block] - self.first[block]
+    }
+
+    fn members(&self, block: usize) -> &[usize] {
+        &self.elements[self.first[block]..self.end[block]]
+    }
+
+    fn mark(&mut self, e: usize) {
+        let b = self.block[e];
+        let i = self.location[e];
+        let j = self.mid[b];
+        if i < j {
+            return;
+        }
+        self.elements.swap(i, j);
+        self.location[self.elements[i]] = i;
+        self.location[self.elements[j]] = j;
+        if self.mid[b] == self.first[b] {
+            self.touched.push(b);
+        }
+        self.mid[b] += 1;
+    }
+
+    /// Splits every touched block into its marked and unmarked members and
+    /// returns the `(old, new)` pairs of blocks that were actually split.
+    fn split(&mut self) -> Vec<(usize, usize)> {
+        let mut result = vec![];
+        while let Some(b) = self.touched.pop() {
+            if self.mid[b] == self.end[b] {
+                self.mid[b] = self.first[b];
                 continue;
             }
-            if component[i] == -1 {
-                component_count += 1;
-                component[i] = component_count;
-                for j in (i + 1)..n {
-                    if reachable[j] && !marked[i][j] {
-                        component[j] = component_count;
-                    }
-                }
+            let new_block = self.len();
+            self.first.push(self.first[b]);
+            self.mid.push(self.first[b]);
+            self.end.push(self.mid[b]);
+            self.first[b] = self.mid[b];
+            for i in self.first[new_block]..self.end[new_block] {
+                self.block[self.elements[i]] = new_block;
+            }
+            result.push((b, new_block));
+        }
+        result
+    }
+}
+
+/// Hopcroft's partition refinement, O(n·|Σ|·log n) over the states that are
+/// reachable from the starting state.
+impl ToMinimalCompleteDfa for Dfa {
+    fn to_mcdfa(&self) -> Dfa {
+        let cdfa = self.to_cdfa();
+        let alphabet: Vec<char> = cdfa.get_alphabet().into_iter().collect();
+        let k = alphabet.len();
+
+        let mut id: HashMap<usize, usize> = HashMap::new();
+        let mut order: Vec<usize> = vec![cdfa.starting_state];
+        let mut delta: Vec<usize> = vec![];
+        id.insert(cdfa.starting_state, 0);
+        let mut cur = 0;
+        while cur < order.len() {
+            let mut row = vec![0; k];
+            for (ch, next_state) in cdfa.states[&order[cur]].transitions.iter() {
+                let next = *id.entry(*next_state).or_insert_with(|| {
+                    order.push(*next_state);
+                    order.len() - 1
+                });
+                row[alphabet.binary_search(ch).unwrap()] = next;
             }
+            delta.extend(row);
+            cur += 1;
         }
+        let n = order.len();
+        let is_terminal: Vec<bool> = order.iter().map(|idx| cdfa.states[idx].is_terminal).collect();
 
-        let mut mcdfa = Dfa::new(component[cdfa.starting_state] as usize);
-        
-        for i in 0..(component_count + 1) {
-            let mut is_terminal = true;
-            let mut transitions: Vec<BTreeSet<char>> = vec![BTreeSet::new(); (component_count + 1) as usize];
-            for j in 0..n {
-                if component[j] != i || !reachable[j] {
-                    continue;
-                }
-                is_terminal &= cdfa.states[&j].is_terminal;
-                for (ch, state) in cdfa.states[&j].transitions.clone() {
-                    transitions[component[state as usize] as usize].insert(ch);
+        // Predecessors of state `t` by symbol `a` are
+        // `sources[offsets[a * n + t]..offsets[a * n + t + 1]]`.
+        let mut offsets = vec![0; k * n + 1];
+        for s in 0..n {
+            for a in 0..k {
+                offsets[a * n + delta[s * k + a] + 1] += 1;
+            }
+        }
+        for i in 0..k * n {
+            offsets[i + 1] += offsets[i];
+        }
+        let mut sources = vec![0; k * n];
+        let mut filled = offsets.clone();
+        for s in 0..n {
+            for a in 0..k {
+                let key = a * n + delta[s * k + a];
+                sources[filled[key]] = s;
+                filled[key] += 1;
+            }
+        }
+
+        let mut partition = Partition::new(&is_terminal);
+        let mut waiting: Vec<(usize, usize)> = vec![];
+        let mut in_waiting: Vec<Vec<bool>> = vec![vec![false; k]; partition.len()];
+        let smallest = (0..partition.len()).min_by_key(|b| partition.size(*b)).unwrap();
+        if partition.len() > 1 {
+            waiting.extend((0..k).map(|a| (smallest, a)));
+            in_waiting[smallest] = vec![true; k];
+        }
+
+        while let Some((splitter, a)) = waiting.pop() {
+            in_waiting[splitter][a] = false;
+            let members = partition.members(splitter).to_vec();
+            for t in members {
+                for s in &sources[offsets[a * n + t]..offsets[a * n + t + 1]] {
+                    partition.mark(*s);
                 }
             }
-            let mut new_state = DfaState::new(i as usize, is_terminal);
-            for (j, set) in transitions.iter().enumerate() {
-                for ch in set {
-                    new_state.add_transition(*ch, j);
+            for (old, new) in partition.split() {
+                in_waiting.push(vec![false; k]);
+                let smaller = if partition.size(new) < partition.size(old) { new } else { old };
+                let pending = in_waiting[old].clone();
+                for (b, old_waiting) in pending.into_iter().enumerate() {
+                    let block = if old_waiting { new } else { smaller };
+                    if !in_waiting[block][b] {
+                        in_waiting[block][b] = true;
+                        waiting.push((block, b));
+                    }
                 }
             }
+        }
+
+        // Blocks are numbered in breadth-first order from the starting block.
+        let mut number: Vec<Option<usize>> = vec![None; partition.len()];
+        let mut blocks: Vec<usize> = vec![partition.block[0]];
+        number[partition.block[0]] = Some(0);
+        let mut mcdfa = Dfa::new(0);
+        let mut cur = 0;
+        while cur < blocks.len() {
+            let representative = partition.members(blocks[cur])[0];
+            let mut new_state = DfaState::new(cur, is_terminal[representative]);
+            for (a, ch) in alphabet.iter().enumerate() {
+                let target = partition.block[delta[representative * k + a]];
+                let next = *number[target].get_or_insert_with(|| {
+                    blocks.push(target);
+                    blocks.len() - 1
+                });
+                new_state.add_transition(*ch, next);
+            }
             mcdfa.add_state(new_state);
+            cur += 1;
         }
         mcdfa
     }
 }
 
 impl Dfa {
+    #[allow(clippy::for_kv_map)]
     pub fn complement(&self) -> Dfa {
         let mut cdfa = self.to_cdfa();
         for (_, state) in cdfa.states.iter_mut() {
@@ -228,6 +623,20 @@ impl Dfa {
         cdfa
     }
 
+    /// Words over exactly `alphabet` that are rejected: symbols outside
+    /// `alphabet` are dropped before completing, unlike `to_cdfa_over`.
+    pub fn complement_over(&self, alphabet: &BTreeSet<char>) -> Dfa {
+        let mut restricted = self.clone();
+        for state in restricted.states.values_mut() {
+            state.retain_transitions(|ch| alphabet.contains(&ch));
+        }
+        let mut cdfa = restricted.to_cdfa_over(alphabet);
+        for state in cdfa.states.values_mut() {
+            state.is_terminal = !state.is_terminal;
+        }
+        cdfa
+    }
+
     pub fn to_nfa(&self) -> Nfa<String> {
         let mut nfa: Nfa<String> = Nfa::new(self.starting_state);
         for (idx, state) in self.states.iter() {
@@ -250,12 +659,18 @@ pub trait ToDfa {
     fn to_dfa(&self) -> Dfa;
 }
 
+impl ToDfa for Dfa {
+    fn to_dfa(&self) -> Dfa {
+        self.clone()
+    }
+}
+
 impl fmt::Display for Dfa {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Starting state: {}", self.starting_state)?;
         for (idx, state) in self.states.iter() {
             for (word, next_state) in state.transitions.iter() {
-                writeln!(f, "{idx} -> {next_state} by \"{word}\"")?;
+                writeln!(f, "{idx} -> {next_state} by \"{}\"", escape(&word.to_string()))?;
             }
         }
         writeln!(f, "Terminal states:")?;
@@ -1,4 +1,9 @@
+use std::collections::BTreeSet;
+
+use crate::automaton::*;
 use crate::dfa::*;
+use crate::error::AutomatonError;
+use crate::testing::*;
 
 #[test]
 fn dfa_to_cdfa_test() {
@@ -17,6 +22,7 @@ fn dfa_to_cdfa_test() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn test_mcdfa() {
     let mut a = DfaState::new(0, false);
     let mut b = DfaState::new(1, false);
@@ -80,4 +86,224 @@ fn duplicate_index_test() {
     let mut dfa = Dfa::new(0);
     dfa.add_state(q0);
     dfa.add_state(q1);
-}
\ No newline at end of file
+}
+
+#[test]
+fn mcdfa_shared_target_test() {
+    let mut q0 = DfaState::new(0, false);
+    q0.add_transition('a', 1);
+    q0.add_transition('\0', 1);
+    q0.add_transition('b', 2);
+
+    let mut q1 = DfaState::new(1, true);
+    q1.add_transition('a', 1);
+    q1.add_transition('\0', 1);
+
+    let mut q2 = DfaState::new(2, false);
+    q2.add_transition('b', 3);
+
+    let q3 = DfaState::new(3, true);
+
+    let mut dfa = Dfa::new(0);
+    dfa.add_state(q0);
+    dfa.add_state(q1);
+    dfa.add_state(q2);
+    dfa.add_state(q3);
+
+    let mcdfa = dfa.to_mcdfa();
+
+    assert_eq!(mcdfa.states.len(), 5);
+    assert!(mcdfa.accept("a"));
+    assert!(mcdfa.accept("\0"));
+    assert!(mcdfa.accept("a\0a"));
+    assert!(mcdfa.accept("bb"));
+    assert!(!mcdfa.accept("b"));
+    assert!(!mcdfa.accept("ab"));
+    assert!(!mcdfa.accept("bbb"));
+}
+
+#[test]
+fn mcdfa_large_test() {
+    let n = 30000;
+    let mut dfa = Dfa::new(0);
+    for i in 0..n {
+        let mut state = DfaState::new(i, i % 3 == 0);
+        state.add_transition('a', (i + 1) % n);
+        state.add_transition('b', (i + 3) % n);
+        dfa.add_state(state);
+    }
+
+    let mcdfa = dfa.to_mcdfa();
+
+    assert_eq!(mcdfa.states.len(), 3);
+    assert!(mcdfa.accept("aaa"));
+    assert!(mcdfa.accept("babbaba"));
+    assert!(!mcdfa.accept("aab"));
+}
+
+#[test]
+fn product_test() {
+    // Even number of 'a' over {a, b}.
+    let mut p0 = DfaState::new(0, true);
+    p0.add_transition('a', 1);
+    p0.add_transition('b', 0);
+    let mut p1 = DfaState::new(1, false);
+    p1.add_transition('a', 0);
+    p1.add_transition('b', 1);
+    let mut even = Dfa::new(0);
+    even.add_state(p0);
+    even.add_state(p1);
+
+    // Words over {b, c} ending with 'c'.
+    let mut q0 = DfaState::new(0, false);
+    q0.add_transition('b', 0);
+    q0.add_transition('c', 1);
+    let mut q1 = DfaState::new(1, true);
+    q1.add_transition('b', 0);
+    q1.add_transition('c', 1);
+    let mut ends_c = Dfa::new(0);
+    ends_c.add_state(q0);
+    ends_c.add_state(q1);
+
+    let intersection = even.intersection(&ends_c);
+    let union = even.union(&ends_c);
+    let difference = even.difference(&ends_c);
+    let xor = even.symmetric_difference(&ends_c);
+
+    assert_eq!(intersection.get_alphabet().len(), 3);
+    for word in words(&['a', 'b', 'c'], 6) {
+        let (a, b) = (even.accept(&word), ends_c.accept(&word));
+        assert_eq!(intersection.accept(&word), a && b, "{word}");
+        assert_eq!(union.accept(&word), a || b, "{word}");
+        assert_eq!(difference.accept(&word), a && !b, "{word}");
+        assert_eq!(xor.accept(&word), a != b, "{word}");
+    }
+}
+
+#[test]
+fn product_reachable_test() {
+    let mut q0 = DfaState::new(0, false);
+    q0.add_transition('a', 1);
+    let q1 = DfaState::new(1, true);
+    let mut dfa = Dfa::new(0);
+    dfa.add_state(q0);
+    dfa.add_state(q1);
+
+    // (0, 0), (1, 1) and the pair of sinks.
+    assert_eq!(dfa.intersection(&dfa).states.len(), 3);
+}
+
+#[test]
+fn witness_test() {
+    let mut q0 = DfaState::new(0, false);
+    q0.add_transition('b', 1);
+    q0.add_transition('a', 2);
+    let mut q1 = DfaState::new(1, true);
+    q1.add_transition('a', 3);
+    let mut q2 = DfaState::new(2, false);
+    q2.add_transition('b', 3);
+    q2.add_transition('a', 2);
+    let q3 = DfaState::new(3, true);
+
+    let mut dfa = Dfa::new(0);
+    dfa.add_state(q0);
+    dfa.add_state(q1);
+    dfa.add_state(q2);
+    dfa.add_state(q3);
+
+    assert_eq!(dfa.shortest_accepted(), Some(String::from("b")));
+    assert_eq!(dfa.shortest_rejected(&BTreeSet::from(['a', 'b'])), Some(String::from("")));
+    assert_eq!(dfa.longest_accepted(), None);
+
+    dfa.states.get_mut(&2).unwrap().retain_transitions(|ch| ch != 'a');
+    assert_eq!(dfa.longest_accepted(), Some(String::from("ab")));
+    assert_eq!(dfa.complement().shortest_rejected(&BTreeSet::from(['a', 'b'])), Some(String::from("b")));
+}
+
+#[test]
+fn dfa_builder_test() {
+    let dfa = Dfa::builder()
+        .start(0)
+        .state(0, false)
+        .state(1, true)
+        .transition(0, 'a', 1)
+        .transition(1, 'a', 0)
+        .build()
+        .unwrap();
+    assert!(dfa.validate().is_ok());
+    assert_eq!(dfa.shortest_accepted().as_deref(), Some("a"));
+
+    let builder = Dfa::builder().start(0).state(0, true).transition(0, 'a', 0);
+    assert_eq!(builder.clone().transition(0, 'a', 0).build().err().unwrap(),
+               AutomatonError::NondeterministicTransition{state: 0, symbol: 'a'});
+    assert_eq!(builder.clone().transition(0, 'b', 1).build().err().unwrap(),
+               AutomatonError::DanglingTransition{from: 0, to: 1});
+    assert_eq!(builder.clone().state(0, false).build().err().unwrap(), AutomatonError::DuplicateState(0));
+    assert_eq!(Dfa::builder().build().err().unwrap(), AutomatonError::MissingStartState);
+
+    let mut dfa = builder.build().unwrap();
+    dfa.states.get_mut(&0).unwrap().add_transition('a', 0);
+    assert_eq!(dfa.validate().err().unwrap(), AutomatonError::NondeterministicTransition{state: 0, symbol: 'a'});
+}
+
+#[test]
+fn empty_dfa_test() {
+    let dfa = Dfa::new(0);
+    let cdfa = dfa.to_cdfa_over(&BTreeSet::from(['a']));
+    assert_eq!(cdfa.states.len(), 0);
+    assert_eq!(dfa.validate().err().unwrap(), AutomatonError::UnknownStartState(0));
+}
+
+#[test]
+fn dfa_accept_unicode_test() {
+    let dfa = Dfa::builder()
+        .start(0)
+        .state(0, false)
+        .state(1, true)
+        .transition(0, 'ж', 1)
+        .transition(1, '名', 0)
+        .transition(1, 'é', 1)
+        .build()
+        .unwrap();
+    assert!(dfa.accept("ж"));
+    assert!(dfa.accept("жéé名ж"));
+    assert!(!dfa.accept("жé名"));
+    assert!(!dfa.accept("a"));
+    assert!(dfa.accept_chars("ж名ж".chars().rev()));
+    assert!(dfa.accept_chars(vec!['ж', 'é']));
+    assert!(!dfa.accept_chars(std::iter::repeat_n('ж', 2)));
+
+    let dfa = from_regex("(ü+日本)*").to_mcdfa();
+    assert!(dfa.accept("日本üü日本"));
+    assert!(!dfa.accept("日ü本"));
+    assert!(!Dfa::new(0).accept(""));
+}
+
+#[test]
+fn retain_transitions_test() {
+    let mut state = DfaState::new(0, false);
+    state.add_transition('a', 1);
+    state.add_transition('b', 2);
+    state.retain_transitions(|ch| ch != 'a');
+    assert_eq!(state.transitions(), &[('b', 2)]);
+    assert_eq!(state.next_state('a'), None);
+
+    state.add_transition('a', 3);
+    assert_eq!(state.next_state('a'), Some(3));
+    assert_eq!(state.next_state('b'), Some(2));
+}
+
+#[test]
+fn complement_over_test() {
+    let dfa = from_regex("ab*").to_dfa();
+    let ab = BTreeSet::from(['a', 'b']);
+    assert!(dfa.complement_over(&ab).accept("ba"));
+    assert!(!dfa.complement_over(&ab).accept("abb"));
+
+    let complement = dfa.complement_over(&BTreeSet::from(['a', 'c']));
+    assert!(complement.accept("c"));
+    assert!(complement.accept("aa"));
+    assert!(!complement.accept("a"));
+    assert!(!complement.accept("ab"));
+    assert_eq!(complement.get_alphabet(), BTreeSet::from(['a', 'c']));
+}
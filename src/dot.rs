@@ -0,0 +1,503 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Write};
+
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+use crate::parse::{ParseError, RawAutomaton, RawTransition};
+use crate::state::Traversable;
+
+/// Edge labels list the symbols of all transitions between the same pair of
+/// states, separated by ", ". The empty word is written as `ε`, and a
+/// backslash escapes `,`, `\` and a literal `ε`.
+const EPSILON: &str = "ε";
+
+#[derive(Clone, Debug, Default)]
+pub struct DotOptions {
+    /// Graph name after `digraph`.
+    pub name: Option<String>,
+    /// Text shown inside a state instead of its index.
+    pub state_labels: BTreeMap<usize, String>
+}
+
+pub trait ToDot {
+    fn to_dot_with(&self, options: &DotOptions) -> String;
+
+    fn to_dot(&self) -> String {
+        self.to_dot_with(&DotOptions::default())
+    }
+}
+
+fn quote(text: &str) -> String {
+    let mut result = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            _ => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn escape_symbol(word: &str) -> String {
+    if word.is_empty() {
+        return EPSILON.to_string();
+    }
+    if word == EPSILON {
+        return format!("\\{EPSILON}");
+    }
+    word.replace('\\', "\\\\").replace(',', "\\,")
+}
+
+fn write_dot(starting_state: usize, states: &[(usize, bool)], edges: &[(usize, String, usize)],
+             options: &DotOptions) -> String {
+    let mut merged: BTreeMap<(usize, usize), Vec<String>> = BTreeMap::new();
+    for (from, word, to) in edges {
+        merged.entry((*from, *to)).or_default().push(escape_symbol(word));
+    }
+
+    let mut result = String::new();
+    match &options.name {
+        Some(name) => writeln!(result, "digraph {} {{", quote(name)).unwrap(),
+        None => writeln!(result, "digraph {{").unwrap(),
+    }
+    writeln!(result, "    rankdir=LR;").unwrap();
+    writeln!(result, "    __start [shape=point];").unwrap();
+    for (idx, is_terminal) in states {
+        let shape = if *is_terminal { "doublecircle" } else { "circle" };
+        match options.state_labels.get(idx) {
+            Some(label) => writeln!(result, "    {idx} [shape={shape}, label={}];", quote(label)).unwrap(),
+            None => writeln!(result, "    {idx} [shape={shape}];").unwrap(),
+        }
+    }
+    writeln!(result, "    __start -> {starting_state};").unwrap();
+    for ((from, to), words) in merged {
+        writeln!(result, "    {from} -> {to} [label={}];", quote(&words.join(", "))).unwrap();
+    }
+    writeln!(result, "}}").unwrap();
+    result
+}
+
+impl<T: Traversable + Display + Clone> ToDot for Nfa<T> {
+    fn to_dot_with(&self, options: &DotOptions) -> String {
+        let states: Vec<(usize, bool)> = self.states.values().map(|state| (state.index, state.is_terminal)).collect();
+        let mut edges = vec![];
+        for state in self.states.values() {
+            for (word, next_state) in state.transitions.iter() {
+                edges.push((state.index, word.to_string(), *next_state));
+            }
+        }
+        write_dot(self.starting_state, &states, &edges, options)
+    }
+}
+
+impl ToDot for Dfa {
+    fn to_dot_with(&self, options: &DotOptions) -> String {
+        let states: Vec<(usize, bool)> = self.states.values().map(|state| (state.index, state.is_terminal)).collect();
+        let mut edges = vec![];
+        for state in self.states.values() {
+            for (ch, next_state) in state.transitions().iter() {
+                edges.push((state.index, ch.to_string(), *next_state));
+            }
+        }
+        write_dot(self.starting_state, &states, &edges, options)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Id(String),
+    Arrow,
+    Undirected,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Equals,
+    Semicolon,
+    Comma,
+    Eof,
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize
+}
+
+impl Lexer {
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek(0)?;
+        self.pos += 1;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(self.line, self.column, message)
+    }
+
+    fn skip_blank(&mut self) -> Result<(), ParseError> {
+        loop {
+            match (self.peek(0), self.peek(1)) {
+                (Some(ch), _) if ch.is_whitespace() => {
+                    self.bump();
+                }
+                (Some('/'), Some('/')) | (Some('#'), _) => {
+                    while self.peek(0).is_some_and(|ch| ch != '\n') {
+                        self.bump();
+                    }
+                }
+                (Some('/'), Some('*')) => {
+                    let error = self.error("unterminated comment");
+                    self.bump();
+                    self.bump();
+                    while !(self.peek(0) == Some('*') && self.peek(1) == Some('/')) {
+                        self.bump().ok_or(error.clone())?;
+                    }
+                    self.bump();
+                    self.bump();
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn is_id_char(ch: char) -> bool {
+        ch.is_alphanumeric() || ch == '_' || ch == '.' || !ch.is_ascii()
+    }
+
+    fn next_token(&mut self) -> Result<(Token, usize, usize), ParseError> {
+        self.skip_blank()?;
+        let (line, column) = (self.line, self.column);
+        let token = match self.peek(0) {
+            None => Token::Eof,
+            Some('"') => {
+                let error = self.error("unterminated string");
+                self.bump();
+                let mut text = String::new();
+                loop {
+                    match self.bump().ok_or(error.clone())? {
+                        '"' => break,
+                        '\\' => match self.bump().ok_or(error.clone())? {
+                            '"' => text.push('"'),
+                            '\\' => text.push('\\'),
+                            'n' | 'l' | 'r' => text.push('\n'),
+                            '\n' => {}
+                            ch => {
+                                text.push('\\');
+                                text.push(ch);
+                            }
+                        },
+                        ch => text.push(ch),
+                    }
+                }
+                Token::Id(text)
+            }
+            Some('-') if self.peek(1) == Some('>') => {
+                self.bump();
+                self.bump();
+                Token::Arrow
+            }
+            Some('-') if self.peek(1) == Some('-') => {
+                self.bump();
+                self.bump();
+                Token::Undirected
+            }
+            Some(ch) if Self::is_id_char(ch) || ch == '-' => {
+                let mut text = String::new();
+                text.push(self.bump().unwrap());
+                while self.peek(0).is_some_and(Self::is_id_char) {
+                    text.push(self.bump().unwrap());
+                }
+                Token::Id(text)
+            }
+            Some(ch) => {
+                self.bump();
+                match ch {
+                    '{' => Token::LBrace,
+                    '}' => Token::RBrace,
+                    '[' => Token::LBracket,
+                    ']' => Token::RBracket,
+                    '=' => Token::Equals,
+                    ';' => Token::Semicolon,
+                    ',' => Token::Comma,
+                    _ => return Err(ParseError::new(line, column, &format!("unexpected character '{ch}'"))),
+                }
+            }
+        };
+        Ok((token, line, column))
+    }
+}
+
+struct Node {
+    shape: Option<String>
+}
+
+struct Edge {
+    from: String,
+    to: String,
+    label: Option<String>,
+    line: usize,
+    column: usize
+}
+
+/// Graph as read from the DOT subset written by `ToDot`: node shapes and
+/// edge labels, everything else is ignored.
+struct Graph {
+    nodes: Vec<(String, Node)>,
+    edges: Vec<Edge>
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize, usize)>,
+    pos: usize,
+    node_defaults: Option<String>,
+    graph: Graph
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        let (_, line, column) = self.tokens[self.pos];
+        ParseError::new(line, column, message)
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+        if token != Token::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, token: Token, message: &str) -> Result<(), ParseError> {
+        if *self.peek() != token {
+            return Err(self.error(message));
+        }
+        self.next();
+        Ok(())
+    }
+
+    fn id(&mut self, message: &str) -> Result<String, ParseError> {
+        match self.peek().clone() {
+            Token::Id(id) => {
+                self.next();
+                Ok(id)
+            }
+            _ => Err(self.error(message)),
+        }
+    }
+
+    fn attributes(&mut self) -> Result<BTreeMap<String, String>, ParseError> {
+        let mut result = BTreeMap::new();
+        while *self.peek() == Token::LBracket {
+            self.next();
+            while *self.peek() != Token::RBracket {
+                let key = self.id("expected attribute name")?;
+                self.expect(Token::Equals, "expected '='")?;
+                let value = self.id("expected attribute value")?;
+                result.insert(key, value);
+                if matches!(self.peek(), Token::Comma | Token::Semicolon) {
+                    self.next();
+                }
+            }
+            self.next();
+        }
+        Ok(result)
+    }
+
+    fn node(&mut self, id: &str) -> usize {
+        if let Some(i) = self.graph.nodes.iter().position(|(name, _)| name == id) {
+            return i;
+        }
+        let node = Node{shape: self.node_defaults.clone()};
+        self.graph.nodes.push((id.to_string(), node));
+        self.graph.nodes.len() - 1
+    }
+
+    fn statement(&mut self) -> Result<(), ParseError> {
+        let (_, line, column) = self.tokens[self.pos];
+        let id = self.id("expected statement")?;
+        match (id.as_str(), self.peek()) {
+            ("node", Token::LBracket) => {
+                if let Some(shape) = self.attributes()?.remove("shape") {
+                    self.node_defaults = Some(shape);
+                }
+            }
+            ("graph" | "edge", Token::LBracket) => {
+                self.attributes()?;
+            }
+            (_, Token::Equals) => {
+                self.next();
+                self.id("expected value")?;
+            }
+            (_, Token::Arrow) => {
+                let mut chain = vec![(id, line, column)];
+                while *self.peek() == Token::Arrow {
+                    self.next();
+                    let (_, line, column) = self.tokens[self.pos];
+                    chain.push((self.id("expected node after '->'")?, line, column));
+                }
+                let label = self.attributes()?.remove("label");
+                for (name, _, _) in chain.iter() {
+                    self.node(name);
+                }
+                for pair in chain.windows(2) {
+                    self.graph.edges.push(Edge{from: pair[0].0.clone(), to: pair[1].0.clone(),
+                                               label: label.clone(), line: pair[0].1, column: pair[0].2});
+                }
+            }
+            (_, Token::Undirected) => return Err(self.error("undirected edges are not supported")),
+            _ => {
+                let i = self.node(&id);
+                if let Some(shape) = self.attributes()?.remove("shape") {
+                    self.graph.nodes[i].1.shape = Some(shape);
+                }
+            }
+        }
+        if matches!(self.peek(), Token::Semicolon | Token::Comma) {
+            self.next();
+        }
+        Ok(())
+    }
+
+    fn parse(mut self) -> Result<Graph, ParseError> {
+        if *self.peek() == Token::Id(String::from("strict")) {
+            self.next();
+        }
+        if *self.peek() != Token::Id(String::from("digraph")) {
+            return Err(self.error("expected 'digraph'"));
+        }
+        self.next();
+        if matches!(self.peek(), Token::Id(_)) {
+            self.next();
+        }
+        self.expect(Token::LBrace, "expected '{'")?;
+        while !matches!(self.peek(), Token::RBrace | Token::Eof) {
+            self.statement()?;
+        }
+        self.expect(Token::RBrace, "expected '}'")?;
+        if *self.peek() != Token::Eof {
+            return Err(self.error("unexpected input after the graph"));
+        }
+        Ok(self.graph)
+    }
+}
+
+fn split_label(label: &str) -> Vec<String> {
+    let mut result = vec![];
+    let mut word = String::new();
+    let mut escaped = false;
+    let mut chars = label.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                escaped = true;
+                word.extend(chars.next());
+            }
+            ',' => {
+                result.push(if word == EPSILON && !escaped { String::new() } else { word });
+                word = String::new();
+                escaped = false;
+                let rest = chars.as_str();
+                chars = rest.strip_prefix(' ').unwrap_or(rest).chars();
+            }
+            _ => word.push(ch),
+        }
+    }
+    result.push(if word == EPSILON && !escaped { String::new() } else { word });
+    result
+}
+
+/// Nodes with `shape=point` or an id starting with `__start` mark the
+/// starting state with their single outgoing edge. States are numbered by
+/// their ids when these are all distinct non-negative integers, otherwise in
+/// order of first appearance.
+fn read_dot(src: &str) -> Result<RawAutomaton, ParseError> {
+    let mut lexer = Lexer{chars: src.chars().collect(), pos: 0, line: 1, column: 1};
+    let mut tokens = vec![];
+    loop {
+        let token = lexer.next_token()?;
+        let done = token.0 == Token::Eof;
+        tokens.push(token);
+        if done {
+            break;
+        }
+    }
+    let graph = Parser{tokens, pos: 0, node_defaults: None, graph: Graph{nodes: vec![], edges: vec![]}}.parse()?;
+
+    let is_start_marker = |name: &str, node: &Node| node.shape.as_deref() == Some("point") || name.starts_with("__start");
+    let states: Vec<&(String, Node)> = graph.nodes.iter().filter(|(name, node)| !is_start_marker(name, node)).collect();
+    // Ids like "01" and "1" name different nodes but parse to the same
+    // integer, so they fall back to order of appearance too.
+    let numbers: BTreeSet<usize> = states.iter().filter_map(|(name, _)| name.parse().ok()).collect();
+    let numeric = numbers.len() == states.len();
+    let mut index: BTreeMap<&str, usize> = BTreeMap::new();
+    for (i, (name, _)) in states.iter().enumerate() {
+        index.insert(name, if numeric { name.parse().unwrap() } else { i });
+    }
+
+    let mut starting_state = None;
+    let mut transitions = vec![];
+    for edge in graph.edges.iter() {
+        let Some(to) = index.get(edge.to.as_str()) else {
+            return Err(ParseError::new(edge.line, edge.column, "edge into a start marker"));
+        };
+        match index.get(edge.from.as_str()) {
+            None => {
+                if starting_state.is_some() {
+                    return Err(ParseError::new(edge.line, edge.column, "more than one starting state"));
+                }
+                starting_state = Some(*to);
+            }
+            Some(from) => {
+                let Some(label) = &edge.label else {
+                    return Err(ParseError::new(edge.line, edge.column, "edge without label"));
+                };
+                for word in split_label(label) {
+                    transitions.push(RawTransition{from: *from, word, to: *to, line: edge.line, column: edge.column});
+                }
+            }
+        }
+    }
+    let Some(starting_state) = starting_state else {
+        return Err(ParseError::new(1, 1, "no starting state: expected an edge from a shape=point node"));
+    };
+    let states = states.iter()
+        .map(|(name, node)| (index[name.as_str()], node.shape.as_deref() == Some("doublecircle")))
+        .collect();
+    Ok(RawAutomaton{starting_state, states, transitions})
+}
+
+impl Nfa<String> {
+    pub fn from_dot(src: &str) -> Result<Self, ParseError> {
+        Ok(read_dot(src)?.into_nfa())
+    }
+}
+
+impl Dfa {
+    /// Like `Nfa::from_dot`, but every label must be a single symbol and no
+    /// state may have two transitions on the same symbol.
+    pub fn from_dot(src: &str) -> Result<Self, ParseError> {
+        read_dot(src)?.into_dfa()
+    }
+}
+
+#[cfg(test)]
+mod tests;
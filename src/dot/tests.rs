@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use crate::automaton::*;
+use crate::dfa::*;
+use crate::dot::*;
+use crate::language::*;
+use crate::nfa::*;
+use crate::testing::*;
+
+#[test]
+fn nfa_to_dot_test() {
+    let mut q0: NfaState<String> = NfaState::new(0, false);
+    q0.add_transition(String::from("a"), 1);
+    q0.add_transition(String::from("b"), 1);
+    q0.add_transition(String::from(""), 0);
+    let q1: NfaState<String> = NfaState::new(1, true);
+    let mut nfa: Nfa<String> = Nfa::new(0);
+    nfa.add_state(q0);
+    nfa.add_state(q1);
+
+    assert_eq!(nfa.to_dot(), "\
+digraph {
+    rankdir=LR;
+    __start [shape=point];
+    0 [shape=circle];
+    1 [shape=doublecircle];
+    __start -> 0;
+    0 -> 0 [label=\"ε\"];
+    0 -> 1 [label=\"a, b\"];
+}
+");
+}
+
+#[test]
+fn dfa_to_dot_labels_test() {
+    let mut q0 = DfaState::new(0, true);
+    q0.add_transition('"', 0);
+    let mut dfa = Dfa::new(0);
+    dfa.add_state(q0);
+
+    let options = DotOptions{name: Some(String::from("quotes")),
+                             state_labels: BTreeMap::from([(0, String::from("{0, 1}"))])};
+    assert_eq!(dfa.to_dot_with(&options), "\
+digraph \"quotes\" {
+    rankdir=LR;
+    __start [shape=point];
+    0 [shape=doublecircle, label=\"{0, 1}\"];
+    __start -> 0;
+    0 -> 0 [label=\"\\\"\"];
+}
+");
+}
+
+#[test]
+fn dot_round_trip_test() {
+    let mut q0: NfaState<String> = NfaState::new(3, false);
+    for word in [",", "\\", "\"", "ε", "\n", "", "ab", " x", ", "] {
+        q0.add_transition(String::from(word), 7);
+    }
+    let q1: NfaState<String> = NfaState::new(7, true);
+    let mut nfa: Nfa<String> = Nfa::new(3);
+    nfa.add_state(q0);
+    nfa.add_state(q1);
+
+    let parsed = Nfa::from_dot(&nfa.to_dot()).unwrap();
+    assert_eq!(parsed.to_string(), nfa.to_string());
+    assert_eq!(parsed.to_dot(), nfa.to_dot());
+
+    let dfa = from_regex("(a+b)*ab").to_mcdfa();
+    let parsed = Dfa::from_dot(&dfa.to_dot()).unwrap();
+    assert_eq!(parsed.to_dot(), dfa.to_dot());
+    assert_eq!(parsed.check_equivalence(&dfa), Equivalence::Equivalent);
+}
+
+#[test]
+fn foreign_dot_test() {
+    let src = r#"
+        // Drawn by hand.
+        digraph finite_state_machine {
+            rankdir=LR; size="8,5"
+            node [shape = doublecircle]; q2;
+            node [shape = circle];
+            start [shape=point];
+            start -> q0;
+            q0 -> q1 [ label = "a" ];
+            q1 -> q2 [ label = "b" ];
+            /* back to the start */
+            q2 -> q0 [ label = "a" ];
+        }
+    "#;
+
+    let nfa = Nfa::from_dot(src).unwrap();
+    assert_eq!(nfa.states.len(), 3);
+    assert!(nfa.accept("ab"));
+    assert!(nfa.accept("abaab"));
+    assert!(!nfa.accept("aba"));
+
+    let dfa = Dfa::from_dot(src).unwrap();
+    assert_eq!(dfa.check_equivalence(&nfa), Equivalence::Equivalent);
+}
+
+#[test]
+fn dot_colliding_ids_test() {
+    let src = "digraph {\n  __start -> 1;\n  01 [shape=doublecircle];\n  1 -> 01 [label=\"a\"];\n  01 -> 1 [label=\"b\"];\n}";
+    let nfa = Nfa::from_dot(src).unwrap();
+    assert_eq!(nfa.states.len(), 2);
+    assert!(nfa.accept("a"));
+    assert!(nfa.accept("aba"));
+    assert!(!nfa.accept(""));
+    assert!(!nfa.accept("ab"));
+}
+
+#[test]
+fn dot_error_test() {
+    let error = Nfa::from_dot("digraph {\n  0 -> 1 [label=\"a\"];\n}").err().unwrap();
+    assert_eq!((error.line, error.column), (1, 1));
+
+    let error = Nfa::from_dot("digraph {\n  __start -> 0;\n  0 -> 1 [label=\"a];\n}").err().unwrap();
+    assert_eq!((error.line, error.column), (3, 17));
+    assert_eq!(error.message, "unterminated string");
+
+    let error = Dfa::from_dot("digraph {\n  __start -> 0;\n  0 -> 1 [label=\"a, bc\"];\n}").err().unwrap();
+    assert_eq!((error.line, error.column), (3, 3));
+
+    let error = Dfa::from_dot("digraph {\n  __start -> 0;\n  0 -> 1 [label=\"a\"];\n  0 -> 0 [label=\"a\"];\n}").err().unwrap();
+    assert_eq!((error.line, error.column), (4, 3));
+
+    let error = Nfa::from_dot("graph {\n}").err().unwrap();
+    assert_eq!((error.line, error.column), (1, 1));
+}
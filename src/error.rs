@@ -0,0 +1,64 @@
+use std::fmt;
+
+use crate::parse::ParseError;
+use crate::regex::RegexError;
+
+/// Error of the fallible constructors of the crate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AutomatonError {
+    /// Two states share an index.
+    DuplicateState(usize),
+    /// No starting state was given.
+    MissingStartState,
+    /// The starting state is not one of the states.
+    UnknownStartState(usize),
+    /// A transition leaves from or leads to a state that does not exist.
+    DanglingTransition{from: usize, to: usize},
+    /// A state of a `Dfa` has two transitions on the same symbol.
+    NondeterministicTransition{state: usize, symbol: char},
+    Parse(ParseError),
+    Regex(RegexError),
+}
+
+impl fmt::Display for AutomatonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutomatonError::DuplicateState(idx) => write!(f, "duplicate state {idx}"),
+            AutomatonError::MissingStartState => write!(f, "no starting state"),
+            AutomatonError::UnknownStartState(idx) => write!(f, "starting state {idx} does not exist"),
+            AutomatonError::DanglingTransition{from, to} => {
+                write!(f, "transition {from} -> {to} uses a state that does not exist")
+            }
+            AutomatonError::NondeterministicTransition{state, symbol} => {
+                write!(f, "state {state} has a second transition on '{symbol}'")
+            }
+            AutomatonError::Parse(error) => write!(f, "{error}"),
+            AutomatonError::Regex(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for AutomatonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AutomatonError::Parse(error) => Some(error),
+            AutomatonError::Regex(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for AutomatonError {
+    fn from(error: ParseError) -> Self {
+        AutomatonError::Parse(error)
+    }
+}
+
+impl From<RegexError> for AutomatonError {
+    fn from(error: RegexError) -> Self {
+        AutomatonError::Regex(error)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,33 @@
+use std::error::Error;
+
+use crate::dfa::*;
+use crate::error::*;
+use crate::nfa::*;
+use crate::regex::Regex;
+
+fn parse_dfa(src: &str) -> Result<Dfa, AutomatonError> {
+    let dfa: Dfa = src.parse()?;
+    dfa.validate()?;
+    Ok(dfa)
+}
+
+#[test]
+fn error_display_test() {
+    assert_eq!(AutomatonError::DuplicateState(3).to_string(), "duplicate state 3");
+    assert_eq!(AutomatonError::DanglingTransition{from: 0, to: 7}.to_string(),
+               "transition 0 -> 7 uses a state that does not exist");
+    assert_eq!(AutomatonError::NondeterministicTransition{state: 2, symbol: 'x'}.to_string(),
+               "state 2 has a second transition on 'x'");
+    assert!(AutomatonError::MissingStartState.source().is_none());
+}
+
+#[test]
+fn error_conversion_test() {
+    let error = parse_dfa("Starting state: 0\n0 -> 1 by \"ab\"\nTerminal states:\n").err().unwrap();
+    assert!(matches!(error, AutomatonError::Parse(_)));
+    assert_eq!(error.to_string(), "2:1: label \"ab\" is not a single symbol");
+    assert!(error.source().is_some());
+
+    let regex = || -> Result<Nfa<String>, AutomatonError> { Ok(Regex::parse("(a")?.to_nfa()) };
+    assert!(matches!(regex().err().unwrap(), AutomatonError::Regex(_)));
+}
@@ -0,0 +1,458 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write};
+
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+use crate::parse::{ParseError, RawAutomaton, RawTransition};
+use crate::state::Traversable;
+
+/// Version written to the `version` field. Readers accept any version up to
+/// this one and ignore fields they do not know.
+pub const SCHEMA_VERSION: usize = 1;
+
+/// Deepest nesting of arrays and objects the reader accepts. The schema
+/// needs three levels; the limit keeps hostile input from exhausting the
+/// stack.
+pub const MAX_DEPTH: usize = 64;
+
+/// JSON encoding of the form
+///
+/// ```text
+/// {
+///   "version": 1,
+///   "kind": "dfa",
+///   "starting_state": 0,
+///   "states": [
+///     {"index": 0, "terminal": false},
+///     {"index": 1, "terminal": true}
+///   ],
+///   "transitions": [
+///     {"from": 0, "word": "a", "to": 1}
+///   ]
+/// }
+/// ```
+///
+/// `kind` is `"nfa"` for both `Nfa<char>` and `Nfa<String>`, which also
+/// read `"dfa"` documents; the empty word is the label `""`.
+pub trait ToJson {
+    fn to_json(&self) -> String;
+}
+
+fn quote(text: &str) -> String {
+    let mut result = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => write!(result, "\\u{:04x}", ch as u32).unwrap(),
+            _ => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn write_json(kind: &str, starting_state: usize, states: &[(usize, bool)],
+              edges: &[(usize, String, usize)]) -> String {
+    let mut result = String::new();
+    writeln!(result, "{{").unwrap();
+    writeln!(result, "  \"version\": {SCHEMA_VERSION},").unwrap();
+    writeln!(result, "  \"kind\": {},", quote(kind)).unwrap();
+    writeln!(result, "  \"starting_state\": {starting_state},").unwrap();
+    let states: Vec<String> = states.iter()
+        .map(|(idx, is_terminal)| format!("{{\"index\": {idx}, \"terminal\": {is_terminal}}}"))
+        .collect();
+    write_array(&mut result, "states", &states);
+    writeln!(result, ",").unwrap();
+    let edges: Vec<String> = edges.iter()
+        .map(|(from, word, to)| format!("{{\"from\": {from}, \"word\": {}, \"to\": {to}}}", quote(word)))
+        .collect();
+    write_array(&mut result, "transitions", &edges);
+    writeln!(result).unwrap();
+    writeln!(result, "}}").unwrap();
+    result
+}
+
+fn write_array(result: &mut String, key: &str, items: &[String]) {
+    if items.is_empty() {
+        write!(result, "  \"{key}\": []").unwrap();
+        return;
+    }
+    writeln!(result, "  \"{key}\": [").unwrap();
+    writeln!(result, "    {}", items.join(",\n    ")).unwrap();
+    write!(result, "  ]").unwrap();
+}
+
+impl<T: Traversable + Display + Clone> ToJson for Nfa<T> {
+    fn to_json(&self) -> String {
+        let states: Vec<(usize, bool)> = self.states.values().map(|state| (state.index, state.is_terminal)).collect();
+        let mut edges = vec![];
+        for state in self.states.values() {
+            for (word, next_state) in state.transitions.iter() {
+                edges.push((state.index, word.to_string(), *next_state));
+            }
+        }
+        write_json("nfa", self.starting_state, &states, &edges)
+    }
+}
+
+impl ToJson for Dfa {
+    fn to_json(&self) -> String {
+        let states: Vec<(usize, bool)> = self.states.values().map(|state| (state.index, state.is_terminal)).collect();
+        let mut edges = vec![];
+        for state in self.states.values() {
+            for (ch, next_state) in state.transitions().iter() {
+                edges.push((state.index, ch.to_string(), *next_state));
+            }
+        }
+        write_json("dfa", self.starting_state, &states, &edges)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    /// Numbers keep their source text so that large integers are not rounded.
+    Number(String),
+    String(String),
+    Array(Vec<Located>),
+    Object(Vec<(String, Located)>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Located {
+    value: Value,
+    line: usize,
+    column: usize
+}
+
+impl Located {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(self.line, self.column, message)
+    }
+
+    fn field(&self, key: &str) -> Result<&Located, ParseError> {
+        let Value::Object(fields) = &self.value else {
+            return Err(self.error("expected an object"));
+        };
+        fields.iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+            .ok_or_else(|| self.error(&format!("missing field \"{key}\"")))
+    }
+
+    fn as_index(&self) -> Result<usize, ParseError> {
+        match &self.value {
+            Value::Number(text) => text.parse().map_err(|_| self.error("expected a non-negative integer")),
+            _ => Err(self.error("expected a non-negative integer")),
+        }
+    }
+
+    fn as_bool(&self) -> Result<bool, ParseError> {
+        match &self.value {
+            Value::Bool(value) => Ok(*value),
+            _ => Err(self.error("expected true or false")),
+        }
+    }
+
+    fn as_str(&self) -> Result<&str, ParseError> {
+        match &self.value {
+            Value::String(text) => Ok(text),
+            _ => Err(self.error("expected a string")),
+        }
+    }
+
+    fn as_array(&self) -> Result<&[Located], ParseError> {
+        match &self.value {
+            Value::Array(items) => Ok(items),
+            _ => Err(self.error("expected an array")),
+        }
+    }
+}
+
+struct Reader {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+    depth: usize
+}
+
+impl Reader {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += 1;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(self.line, self.column, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|ch| matches!(ch, ' ' | '\t' | '\n' | '\r')) {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, ch: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.peek() != Some(ch) {
+            return Err(self.error(&format!("expected '{ch}'")));
+        }
+        self.bump();
+        Ok(())
+    }
+
+    fn keyword(&mut self, word: &str, value: Value) -> Result<Value, ParseError> {
+        for expected in word.chars() {
+            if self.peek() != Some(expected) {
+                return Err(self.error(&format!("expected \"{word}\"")));
+            }
+            self.bump();
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Located, ParseError> {
+        self.skip_whitespace();
+        let (line, column) = (self.line, self.column);
+        if matches!(self.peek(), Some('{' | '[')) && self.depth == MAX_DEPTH {
+            return Err(self.error("nesting too deep"));
+        }
+        let value = match self.peek() {
+            Some('{') => {
+                self.depth += 1;
+                let value = self.object()?;
+                self.depth -= 1;
+                value
+            }
+            Some('[') => {
+                self.depth += 1;
+                let value = self.array()?;
+                self.depth -= 1;
+                value
+            }
+            Some('"') => Value::String(self.string()?),
+            Some('t') => self.keyword("true", Value::Bool(true))?,
+            Some('f') => self.keyword("false", Value::Bool(false))?,
+            Some('n') => self.keyword("null", Value::Null)?,
+            Some(ch) if ch == '-' || ch.is_ascii_digit() => Value::Number(self.number()?),
+            Some(ch) => return Err(self.error(&format!("unexpected character '{ch}'"))),
+            None => return Err(self.error("unexpected end of input")),
+        };
+        Ok(Located{value, line, column})
+    }
+
+    fn object(&mut self) -> Result<Value, ParseError> {
+        self.bump();
+        let mut fields = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.bump();
+            return Ok(Value::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("expected a field name"));
+            }
+            let key = self.string()?;
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {}
+                Some('}') => {
+                    self.bump();
+                    return Ok(Value::Object(fields));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+            self.bump();
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, ParseError> {
+        self.bump();
+        let mut items = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.bump();
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {}
+                Some(']') => {
+                    self.bump();
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+            self.bump();
+        }
+    }
+
+    fn number(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.bump();
+        }
+        if !self.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+            return Err(self.error("expected a digit"));
+        }
+        while self.peek().is_some_and(|ch| ch.is_ascii_digit() || matches!(ch, '.' | 'e' | 'E' | '+' | '-')) {
+            self.bump();
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn hex4(&mut self) -> Result<u32, ParseError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self.peek().and_then(|ch| ch.to_digit(16)).ok_or_else(|| self.error("expected a hex digit"))?;
+            self.bump();
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let error = self.error("unterminated string");
+        self.bump();
+        let mut text = String::new();
+        loop {
+            match self.peek() {
+                None | Some('\n') => return Err(error),
+                Some('"') => {
+                    self.bump();
+                    return Ok(text);
+                }
+                Some('\\') => {
+                    let escape = self.error("unknown escape sequence");
+                    self.bump();
+                    let ch = match self.bump() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let mut code = self.hex4()?;
+                            if (0xD800..0xDC00).contains(&code) && self.chars[self.pos..].starts_with(&['\\', 'u']) {
+                                self.bump();
+                                self.bump();
+                                let low = self.hex4()?;
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return Err(escape);
+                                }
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+                            char::from_u32(code).ok_or(escape)?
+                        }
+                        _ => return Err(escape),
+                    };
+                    text.push(ch);
+                }
+                Some(ch) => {
+                    self.bump();
+                    text.push(ch);
+                }
+            }
+        }
+    }
+}
+
+/// Reads a document whose `kind` is one of `kinds`.
+fn read_json(src: &str, kinds: &[&str]) -> Result<RawAutomaton, ParseError> {
+    let mut reader = Reader{chars: src.chars().collect(), pos: 0, line: 1, column: 1, depth: 0};
+    let root = reader.value()?;
+    reader.skip_whitespace();
+    if reader.peek().is_some() {
+        return Err(reader.error("unexpected text after the document"));
+    }
+
+    let version = root.field("version")?;
+    if version.as_index()? > SCHEMA_VERSION {
+        return Err(version.error(&format!("unsupported schema version {}", version.as_index()?)));
+    }
+    let kind = root.field("kind")?;
+    if !kinds.contains(&kind.as_str()?) {
+        let expected: Vec<String> = kinds.iter().map(|kind| quote(kind)).collect();
+        return Err(kind.error(&format!("kind must be {}", expected.join(" or "))));
+    }
+
+    let mut states: BTreeMap<usize, bool> = BTreeMap::new();
+    for state in root.field("states")?.as_array()? {
+        let index = state.field("index")?.as_index()?;
+        if states.insert(index, state.field("terminal")?.as_bool()?).is_some() {
+            return Err(state.error(&format!("state {index} is listed twice")));
+        }
+    }
+    let check_state = |value: &Located| -> Result<usize, ParseError> {
+        let idx = value.as_index()?;
+        if !states.contains_key(&idx) {
+            return Err(value.error(&format!("unknown state {idx}")));
+        }
+        Ok(idx)
+    };
+
+    let starting_state = check_state(root.field("starting_state")?)?;
+    let mut transitions = vec![];
+    for transition in root.field("transitions")?.as_array()? {
+        transitions.push(RawTransition{
+            from: check_state(transition.field("from")?)?,
+            word: transition.field("word")?.as_str()?.to_string(),
+            to: check_state(transition.field("to")?)?,
+            line: transition.line,
+            column: transition.column
+        });
+    }
+    Ok(RawAutomaton{starting_state, states, transitions})
+}
+
+impl Nfa<String> {
+    pub fn from_json(src: &str) -> Result<Self, ParseError> {
+        Ok(read_json(src, &["dfa", "nfa"])?.into_nfa())
+    }
+}
+
+impl Nfa<char> {
+    /// Like `Nfa::<String>::from_json`, but every label must be a single
+    /// symbol.
+    pub fn from_json(src: &str) -> Result<Self, ParseError> {
+        read_json(src, &["dfa", "nfa"])?.into_char_nfa()
+    }
+}
+
+impl Dfa {
+    /// Like `Nfa::from_json`, but `kind` must be `"dfa"`, every label must be
+    /// a single symbol and no state may have two transitions on the same
+    /// symbol.
+    pub fn from_json(src: &str) -> Result<Self, ParseError> {
+        read_json(src, &["dfa"])?.into_dfa()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,115 @@
+use crate::automaton::*;
+use crate::dfa::*;
+use crate::json::*;
+use crate::language::*;
+use crate::nfa::*;
+use crate::testing::*;
+
+#[test]
+fn dfa_to_json_test() {
+    let mut q0 = DfaState::new(0, false);
+    q0.add_transition('a', 1);
+    q0.add_transition('"', 0);
+    let q1 = DfaState::new(1, true);
+    let mut dfa = Dfa::new(0);
+    dfa.add_state(q0);
+    dfa.add_state(q1);
+
+    assert_eq!(dfa.to_json(), "\
+{
+  \"version\": 1,
+  \"kind\": \"dfa\",
+  \"starting_state\": 0,
+  \"states\": [
+    {\"index\": 0, \"terminal\": false},
+    {\"index\": 1, \"terminal\": true}
+  ],
+  \"transitions\": [
+    {\"from\": 0, \"word\": \"a\", \"to\": 1},
+    {\"from\": 0, \"word\": \"\\\"\", \"to\": 0}
+  ]
+}
+");
+    let parsed = Dfa::from_json(&dfa.to_json()).unwrap();
+    assert_eq!(parsed.to_json(), dfa.to_json());
+}
+
+#[test]
+fn nfa_json_round_trip_test() {
+    let mut q0: NfaState<String> = NfaState::new(0, false);
+    q0.add_transition(String::from("line\nbreak\\"), 1);
+    q0.add_transition(String::from(""), 1);
+    q0.add_transition(String::from("\u{1}ü😀"), 0);
+    let q1: NfaState<String> = NfaState::new(1, true);
+    let mut nfa: Nfa<String> = Nfa::new(0);
+    nfa.add_state(q0);
+    nfa.add_state(q1);
+
+    let json = nfa.to_json();
+    assert!(json.contains("\\u0001"));
+    let parsed = Nfa::<String>::from_json(&json).unwrap();
+    assert_eq!(parsed.to_string(), nfa.to_string());
+    assert!(parsed.accept("\u{1}ü😀line\nbreak\\"));
+
+    let chars = from_regex("a(b+c)*").split_words();
+    let parsed = Nfa::<char>::from_json(&chars.to_json()).unwrap();
+    assert!(matches!(parsed.check_equivalence(&chars), Equivalence::Equivalent));
+}
+
+#[test]
+fn json_reader_test() {
+    let src = r#"{"kind": "nfa", "starting_state": 3, "version": 1, "comment": null,
+                  "states": [{"terminal": true, "index": 3}],
+                  "transitions": [{"from": 3, "to": 3, "word": "é😀"}],
+                  "extra": [1.5e3, -2, {}]}"#;
+    let nfa = Nfa::<String>::from_json(src).unwrap();
+    assert!(nfa.accept("é😀é😀"));
+    assert!(nfa.accept(""));
+}
+
+#[test]
+fn json_error_test() {
+    let error = Dfa::from_json("{\"version\": 2}").err().unwrap();
+    assert_eq!(error.to_string(), "1:13: unsupported schema version 2");
+
+    let error = Dfa::from_json("{\n  \"version\": 1,\n  \"kind\": \"dfa\"\n  \"states\": []\n}").err().unwrap();
+    assert_eq!((error.line, error.column), (4, 3));
+    assert_eq!(error.message, "expected ',' or '}'");
+
+    let src = "{\"version\": 1, \"kind\": \"dfa\", \"starting_state\": 0,
+\"states\": [{\"index\": 0, \"terminal\": true}],
+\"transitions\": [{\"from\": 0, \"word\": \"a\", \"to\": 0}, {\"from\": 0, \"word\": \"a\", \"to\": 1}]}";
+    let error = Dfa::from_json(src).err().unwrap();
+    assert_eq!((error.line, error.column), (3, 83));
+    assert_eq!(error.message, "unknown state 1");
+
+    let error = Dfa::from_json(&src.replace("\"to\": 1", "\"to\": 0")).err().unwrap();
+    assert_eq!((error.line, error.column), (3, 52));
+    assert_eq!(error.message, "second transition on 'a'");
+
+    let error = Nfa::<char>::from_json(&src.replace("\"to\": 1", "\"to\": 0").replace("\"a\"", "\"ab\"")).err().unwrap();
+    assert_eq!(error.message, "label \"ab\" is not a single symbol");
+
+    let nfa_src = src.replace("\"to\": 1", "\"to\": 0").replace("\"dfa\"", "\"nfa\"");
+    let error = Dfa::from_json(&nfa_src).err().unwrap();
+    assert_eq!((error.line, error.column), (1, 24));
+    assert_eq!(error.message, "kind must be \"dfa\"");
+    assert!(Nfa::<String>::from_json(&nfa_src).is_ok());
+    assert!(Nfa::<char>::from_json(&src.replace("\"to\": 1", "\"to\": 0")).is_ok());
+
+    let error = Nfa::<String>::from_json(&src.replace("\"dfa\"", "\"tnfa\"")).err().unwrap();
+    assert_eq!((error.line, error.column), (1, 24));
+    assert_eq!(error.message, "kind must be \"dfa\" or \"nfa\"");
+}
+
+#[test]
+fn json_depth_limit_test() {
+    let error = Nfa::<String>::from_json(&"[".repeat(5000)).err().unwrap();
+    assert_eq!((error.line, error.column), (1, MAX_DEPTH + 1));
+    assert_eq!(error.message, "nesting too deep");
+
+    let nested = format!("{}{}", "[".repeat(MAX_DEPTH - 1), "]".repeat(MAX_DEPTH - 1));
+    let src = format!("{{\"version\": 1, \"kind\": \"nfa\", \"starting_state\": 0, \"extra\": {nested},
+                        \"states\": [{{\"index\": 0, \"terminal\": true}}], \"transitions\": []}}");
+    assert!(Nfa::<String>::from_json(&src).unwrap().accept(""));
+}
@@ -0,0 +1,219 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::dfa::{Dfa, ToDfa};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Equivalence {
+    Equivalent,
+    /// Shortest word accepted by exactly one of the two automata.
+    Counterexample(String),
+}
+
+pub trait CheckEquivalence {
+    fn check_equivalence<A: ToDfa>(&self, other: &A) -> Equivalence;
+}
+
+impl<T: ToDfa> CheckEquivalence for T {
+    fn check_equivalence<A: ToDfa>(&self, other: &A) -> Equivalence {
+        equivalence(&self.to_dfa(), &other.to_dfa())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inclusion {
+    Included,
+    /// Shortest word accepted by the first automaton but not the second.
+    NotIncluded(String),
+}
+
+pub trait CheckInclusion {
+    /// Decides whether the language of `self` is a subset of the language
+    /// of `other`.
+    fn check_inclusion<A: ToDfa>(&self, other: &A) -> Inclusion;
+}
+
+impl<T: ToDfa> CheckInclusion for T {
+    fn check_inclusion<A: ToDfa>(&self, other: &A) -> Inclusion {
+        // The product completes both sides over the joint alphabet, so words
+        // using symbols unknown to `other` end up in its complement as well.
+        match self.to_dfa().difference(&other.to_dfa()).shortest_accepted() {
+            Some(word) => Inclusion::NotIncluded(word),
+            None => Inclusion::Included,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Emptiness {
+    Empty,
+    /// Shortest accepted word.
+    NonEmpty(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Universality {
+    Universal,
+    /// Shortest rejected word over the given alphabet.
+    NotUniversal(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Finiteness {
+    Finite,
+    Infinite(Pump),
+}
+
+/// Witness of an infinite language: `prefix`, then `cycle` repeated any
+/// number of times, then `suffix` is accepted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pump {
+    pub prefix: String,
+    pub cycle: String,
+    pub suffix: String
+}
+
+impl Pump {
+    pub fn word(&self, repetitions: usize) -> String {
+        format!("{}{}{}", self.prefix, self.cycle.repeat(repetitions), self.suffix)
+    }
+}
+
+pub trait Decide {
+    fn is_empty(&self) -> Emptiness;
+    fn is_universal(&self, alphabet: &BTreeSet<char>) -> Universality;
+    fn is_finite(&self) -> Finiteness;
+}
+
+impl<T: ToDfa> Decide for T {
+    fn is_empty(&self) -> Emptiness {
+        match self.to_dfa().shortest_accepted() {
+            Some(word) => Emptiness::NonEmpty(word),
+            None => Emptiness::Empty,
+        }
+    }
+
+    fn is_universal(&self, alphabet: &BTreeSet<char>) -> Universality {
+        match self.to_dfa().shortest_rejected(alphabet) {
+            Some(word) => Universality::NotUniversal(word),
+            None => Universality::Universal,
+        }
+    }
+
+    fn is_finite(&self) -> Finiteness {
+        let dfa = self.to_dfa();
+        let edges = dfa.useful_transitions();
+        if !edges.contains_key(&dfa.starting_state) {
+            return Finiteness::Finite;
+        }
+
+        // Depth-first search for a cycle among the useful states; `path[i]`
+        // is the symbol leading from `stack[i]` to `stack[i + 1]`.
+        let mut finished: BTreeSet<usize> = BTreeSet::new();
+        let mut on_stack: BTreeSet<usize> = BTreeSet::from([dfa.starting_state]);
+        let mut stack: Vec<(usize, usize)> = vec![(dfa.starting_state, 0)];
+        let mut path: Vec<char> = vec![];
+        while let Some((cur, next)) = stack.last_mut() {
+            let cur = *cur;
+            if let Some((ch, next_state)) = edges[&cur].get(*next) {
+                *next += 1;
+                if on_stack.contains(next_state) {
+                    let start = stack.iter().position(|(state, _)| state == next_state).unwrap();
+                    let mut entry = dfa.clone();
+                    entry.starting_state = *next_state;
+                    return Finiteness::Infinite(Pump{
+                        prefix: path[..start].iter().collect(),
+                        cycle: path[start..].iter().chain(std::iter::once(ch)).collect(),
+                        suffix: entry.shortest_accepted().unwrap()
+                    });
+                }
+                if !finished.contains(next_state) {
+                    on_stack.insert(*next_state);
+                    stack.push((*next_state, 0));
+                    path.push(*ch);
+                }
+            } else {
+                on_stack.remove(&cur);
+                finished.insert(cur);
+                stack.pop();
+                path.pop();
+            }
+        }
+        Finiteness::Finite
+    }
+}
+
+struct UnionFind {
+    parent: Vec<usize>
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        Self{parent: (0..n).collect()}
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut cur = x;
+        while self.parent[cur] != root {
+            cur = std::mem::replace(&mut self.parent[cur], root);
+        }
+        root
+    }
+
+    fn union(&mut self, x: usize, y: usize) -> bool {
+        let (x, y) = (self.find(x), self.find(y));
+        self.parent[x] = y;
+        x != y
+    }
+}
+
+/// Hopcroft–Karp: pairs of states are explored breadth-first and merged in a
+/// union-find structure, so every pair is visited at most once and the first
+/// pair with different terminal flags yields a shortest counterexample.
+fn equivalence(a: &Dfa, b: &Dfa) -> Equivalence {
+    let alphabet: BTreeSet<char> = a.get_alphabet().union(&b.get_alphabet()).copied().collect();
+    let a = a.to_cdfa_over(&alphabet);
+    let b = b.to_cdfa_over(&alphabet);
+
+    let mut id: HashMap<(bool, usize), usize> = HashMap::new();
+    for idx in a.states.keys() {
+        id.insert((false, *idx), id.len());
+    }
+    for idx in b.states.keys() {
+        id.insert((true, *idx), id.len());
+    }
+    let mut classes = UnionFind::new(id.len());
+
+    let mut pairs: Vec<(usize, usize)> = vec![(a.starting_state, b.starting_state)];
+    let mut parent: Vec<Option<(usize, char)>> = vec![None];
+    classes.union(id[&(false, a.starting_state)], id[&(true, b.starting_state)]);
+    let mut cur = 0;
+    while cur < pairs.len() {
+        let (p, q) = pairs[cur];
+        if a.states[&p].is_terminal != b.states[&q].is_terminal {
+            let mut word = vec![];
+            let mut node = cur;
+            while let Some((prev, ch)) = parent[node] {
+                word.push(ch);
+                node = prev;
+            }
+            return Equivalence::Counterexample(word.into_iter().rev().collect());
+        }
+        for ch in alphabet.iter() {
+            let next_p = a.states[&p].next_state(*ch).unwrap();
+            let next_q = b.states[&q].next_state(*ch).unwrap();
+            if classes.union(id[&(false, next_p)], id[&(true, next_q)]) {
+                pairs.push((next_p, next_q));
+                parent.push(Some((cur, *ch)));
+            }
+        }
+        cur += 1;
+    }
+    Equivalence::Equivalent
+}
+
+#[cfg(test)]
+mod tests;
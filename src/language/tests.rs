@@ -0,0 +1,148 @@
+use crate::dfa::*;
+use crate::language::*;
+use crate::nfa::*;
+use crate::automaton::*;
+use crate::testing::*;
+use std::collections::BTreeSet;
+
+#[test]
+fn equivalent_test() {
+    assert_eq!(from_regex("(a+b)*").check_equivalence(&from_regex("(a*b*)*")), Equivalence::Equivalent);
+    assert_eq!(from_regex("a(ba)*").check_equivalence(&from_regex("(ab)*a")), Equivalence::Equivalent);
+    assert_eq!(from_regex("").check_equivalence(&from_regex("()*")), Equivalence::Equivalent);
+}
+
+#[test]
+fn counterexample_test() {
+    assert_eq!(from_regex("a*").check_equivalence(&from_regex("(aa)*")), Equivalence::Counterexample(String::from("a")));
+    assert_eq!(from_regex("aa*").check_equivalence(&from_regex("a*")), Equivalence::Counterexample(String::from("")));
+    assert_eq!(from_regex("ab+ba").check_equivalence(&from_regex("ab")), Equivalence::Counterexample(String::from("ba")));
+    assert_eq!(from_regex("a").check_equivalence(&from_regex("a+b")), Equivalence::Counterexample(String::from("b")));
+    assert_eq!(from_regex("(a+b)*abb").check_equivalence(&from_regex("(a+b)*bb")),
+               Equivalence::Counterexample(String::from("bb")));
+}
+
+#[test]
+fn equivalence_across_types_test() {
+    let mut q0: NfaState<char> = NfaState::new(0, false);
+    q0.add_transition('a', 1);
+    q0.add_transition('a', 2);
+    q0.add_transition('b', 2);
+
+    let mut q1: NfaState<char> = NfaState::new(1, false);
+    q1.add_transition('a', 2);
+    q1.add_transition('b', 3);
+
+    let mut q2: NfaState<char> = NfaState::new(2, false);
+    q2.add_transition('a', 1);
+    q2.add_transition('a', 2);
+    q2.add_transition('b', 3);
+
+    let q3: NfaState<char> = NfaState::new(3, true);
+
+    let mut nfa_char: Nfa<char> = Nfa::new(0);
+    nfa_char.add_state(q0);
+    nfa_char.add_state(q1);
+    nfa_char.add_state(q2);
+    nfa_char.add_state(q3);
+
+    let mcdfa: Dfa = nfa_char.to_mcdfa();
+    assert_eq!(mcdfa.check_equivalence(&nfa_char), Equivalence::Equivalent);
+    assert_eq!(nfa_char.check_equivalence(&from_regex(&nfa_char.to_regex())), Equivalence::Equivalent);
+    assert_eq!(nfa_char.check_equivalence(&from_regex("bb+(a+ba)(a)*b")), Equivalence::Equivalent);
+    assert_eq!(mcdfa.complement().check_equivalence(&nfa_char), Equivalence::Counterexample(String::from("")));
+}
+
+#[test]
+fn to_regex_round_trip_test() {
+    let mut patterns: Vec<String> = ["(baa)+ba", "bb+(a+ba)(a)*b", "(b)*a((a+b))*", "a(b+)(+a)b",
+                                     "(ab+b)*(+a)", "((a+b)b)*a+b", "(a+)(b+)(a+b)"]
+        .iter().map(|pattern| pattern.to_string()).collect();
+    let parts = ["a", "b", "", "ab", "a*", "(a+b)", "(ba)*", "(+a)"];
+    for x in parts {
+        for y in parts {
+            patterns.push(format!("{x}+{y}"));
+            patterns.push(format!("({x}+{y})b{y}"));
+            patterns.push(format!("({x}+b)*{y}+a"));
+        }
+    }
+    for pattern in patterns {
+        let source = from_regex(&pattern);
+        let regex = source.to_regex();
+        assert_eq!(source.check_equivalence(&from_regex(&regex)), Equivalence::Equivalent, "{pattern} -> {regex}");
+    }
+}
+
+#[test]
+fn inclusion_test() {
+    assert_eq!(from_regex("ab").check_inclusion(&from_regex("(a+b)*")), Inclusion::Included);
+    assert_eq!(from_regex("(aa)*").check_inclusion(&from_regex("a*")), Inclusion::Included);
+    assert_eq!(from_regex("a*").check_inclusion(&from_regex("a*")), Inclusion::Included);
+    assert_eq!(from_regex("a*").check_inclusion(&from_regex("(aa)*")), Inclusion::NotIncluded(String::from("a")));
+    assert_eq!(from_regex("a+c").check_inclusion(&from_regex("(a+b)*")), Inclusion::NotIncluded(String::from("c")));
+    assert_eq!(from_regex("(a+b)*").check_inclusion(&from_regex("a*b*")), Inclusion::NotIncluded(String::from("ba")));
+}
+
+#[test]
+fn inclusion_dfa_test() {
+    let sanitizer: Dfa = from_regex("(ab)*").to_dfa();
+    let parser: Dfa = from_regex("(a+b)*").to_mcdfa();
+
+    assert_eq!(sanitizer.check_inclusion(&parser), Inclusion::Included);
+    assert_eq!(parser.check_inclusion(&sanitizer), Inclusion::NotIncluded(String::from("a")));
+}
+
+#[test]
+fn emptiness_test() {
+    let mut q0: NfaState<String> = NfaState::new(0, false);
+    q0.add_transition(String::from("a"), 1);
+    let q1: NfaState<String> = NfaState::new(1, false);
+    let mut empty: Nfa<String> = Nfa::new(0);
+    empty.add_state(q0);
+    empty.add_state(q1);
+
+    assert_eq!(empty.is_empty(), Emptiness::Empty);
+    assert_eq!(from_regex("").is_empty(), Emptiness::NonEmpty(String::from("")));
+    assert_eq!(from_regex("b*a(a+b)").is_empty(), Emptiness::NonEmpty(String::from("aa")));
+    assert_eq!(from_regex("ba+ab").to_dfa().is_empty(), Emptiness::NonEmpty(String::from("ab")));
+}
+
+#[test]
+fn universality_test() {
+    let ab = BTreeSet::from(['a', 'b']);
+
+    assert_eq!(from_regex("(a+b)*").is_universal(&ab), Universality::Universal);
+    assert_eq!(from_regex("(a*b*)*").to_mcdfa().is_universal(&ab), Universality::Universal);
+    assert_eq!(from_regex("(a+b+c)*").is_universal(&ab), Universality::Universal);
+    assert_eq!(from_regex("(a+b)*").is_universal(&BTreeSet::from(['a', 'b', 'c'])),
+               Universality::NotUniversal(String::from("c")));
+    assert_eq!(from_regex("a*").is_universal(&ab), Universality::NotUniversal(String::from("b")));
+    assert_eq!(from_regex("(a+b)(a+b)*").is_universal(&ab), Universality::NotUniversal(String::from("")));
+}
+
+#[test]
+fn finiteness_test() {
+    assert_eq!(from_regex("ab+ba+").is_finite(), Finiteness::Finite);
+    assert_eq!(from_regex("a(b+c)(a+b)").to_dfa().is_finite(), Finiteness::Finite);
+
+    let mut q0: NfaState<String> = NfaState::new(0, false);
+    q0.add_transition(String::from("a"), 1);
+    let mut q1: NfaState<String> = NfaState::new(1, false);
+    q1.add_transition(String::from("a"), 1);
+    let mut dead_loop: Nfa<String> = Nfa::new(0);
+    dead_loop.add_state(q0);
+    dead_loop.add_state(q1);
+    assert_eq!(dead_loop.is_finite(), Finiteness::Finite);
+
+    let source = from_regex("a(bc)*d+e");
+    match source.is_finite() {
+        Finiteness::Infinite(pump) => {
+            assert!(!pump.cycle.is_empty());
+            for repetitions in 0..4 {
+                assert!(source.accept(&pump.word(repetitions)));
+            }
+        }
+        Finiteness::Finite => panic!("language is infinite"),
+    }
+}
+
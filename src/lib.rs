@@ -0,0 +1,24 @@
+pub mod automaton;
+pub mod state;
+pub mod nfa;
+pub mod dfa;
+pub mod regex;
+pub mod language;
+pub mod words;
+pub mod bigint;
+pub mod count;
+pub mod sample;
+pub mod parse;
+pub mod dot;
+pub mod text;
+pub mod json;
+pub mod error;
+pub mod matcher;
+pub mod search;
+pub mod tnfa;
+pub mod reverse;
+pub mod combinators;
+pub mod simplify;
+
+#[cfg(test)]
+mod testing;
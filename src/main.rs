@@ -1,24 +1,23 @@
-mod automaton;
-mod state;
-mod nfa;
-mod dfa;
+use std::io;
+use std::process::ExitCode;
 
-use automaton::ToRegex;
+mod cli;
+mod repl;
 
-use crate::nfa::*;
-fn main() {
-    let mut q0: NfaState<String> = NfaState::new(0, false);
-    q0.add_transition(String::from("a"), 0);
-    q0.add_transition(String::from("b"), 0);
-    q0.add_transition(String::from("a"), 1);
-    
-    let mut q1: NfaState<String> = NfaState::new(1, true);
-    q1.add_transition(String::from("b"), 1);
-    q1.add_transition(String::from("b"), 0);
-    
-    let mut nfa: Nfa<String> = Nfa::new(0);
-    nfa.add_state(q0);
-    nfa.add_state(q1);
-
-    println!("{}", nfa.to_regex());
-}
\ No newline at end of file
+fn main() -> ExitCode {
+    let options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("automaton: {error}\n\n{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match cli::run(&options, &mut io::stdin().lock(), &mut io::stdout().lock()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("automaton: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
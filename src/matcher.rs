@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read};
+
+use crate::dfa::ToDfa;
+
+/// Verdict on the input fed to a `Matcher` so far.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The input so far is accepted.
+    Accepted,
+    /// The input so far is rejected, but some continuation is accepted.
+    Possible,
+    /// No continuation of the input so far is accepted.
+    Dead,
+}
+
+#[derive(Debug)]
+pub enum MatchError {
+    /// The bytes starting at `offset` of the whole input are not UTF-8.
+    InvalidUtf8{offset: u64},
+    Io(io::Error),
+}
+
+impl fmt::Display for MatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchError::InvalidUtf8{offset} => write!(f, "invalid UTF-8 at byte {offset}"),
+            MatchError::Io(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for MatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MatchError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MatchError {
+    fn from(error: io::Error) -> Self {
+        MatchError::Io(error)
+    }
+}
+
+/// Runs an automaton over input that arrives in pieces. Only the current
+/// state and at most three bytes of an unfinished UTF-8 sequence are kept,
+/// so the input can be arbitrarily long.
+#[derive(Clone)]
+pub struct Matcher {
+    /// Only the useful states of the determinized automaton are kept, so
+    /// every state is live and a missing transition means `Dead`.
+    starting_state: Option<usize>,
+    is_terminal: Vec<bool>,
+    transitions: Vec<HashMap<char, usize>>,
+    state: Option<usize>,
+    /// Leading bytes of a character split across chunks.
+    partial: Vec<u8>,
+    /// Bytes fed so far, for error offsets.
+    offset: u64
+}
+
+impl Matcher {
+    pub fn new<A: ToDfa>(automaton: &A) -> Self {
+        let trimmed = automaton.to_dfa().trimmed();
+        let transitions = trimmed.transitions.iter()
+            .map(|edges| {
+                let mut lookup = HashMap::new();
+                for (ch, next_state) in edges {
+                    lookup.entry(*ch).or_insert(*next_state);
+                }
+                lookup
+            })
+            .collect();
+        Self{starting_state: trimmed.starting_state, is_terminal: trimmed.is_terminal, transitions,
+             state: trimmed.starting_state, partial: vec![], offset: 0}
+    }
+
+    /// Forgets the input fed so far.
+    pub fn reset(&mut self) {
+        self.state = self.starting_state;
+        self.partial.clear();
+        self.offset = 0;
+    }
+
+    /// Verdict on the complete characters fed so far.
+    pub fn status(&self) -> Status {
+        match self.state {
+            Some(state) if self.is_terminal[state] => Status::Accepted,
+            Some(_) => Status::Possible,
+            None => Status::Dead,
+        }
+    }
+
+    pub fn feed_str(&mut self, chunk: &str) -> Status {
+        self.offset += chunk.len() as u64;
+        self.feed_chars(chunk.chars())
+    }
+
+    pub fn feed_chars<I: IntoIterator<Item = char>>(&mut self, chars: I) -> Status {
+        for ch in chars {
+            let Some(state) = self.state else {
+                break;
+            };
+            self.state = self.transitions[state].get(&ch).copied();
+        }
+        self.status()
+    }
+
+    /// Feeds UTF-8 bytes. A character may be split between this chunk and
+    /// the next one; `finish` reports one that is never completed.
+    pub fn feed_bytes(&mut self, chunk: &[u8]) -> Result<Status, MatchError> {
+        if self.state.is_none() {
+            self.offset += chunk.len() as u64;
+            return Ok(Status::Dead);
+        }
+        let mut rest = chunk;
+        if !self.partial.is_empty() {
+            // Complete the split character one byte at a time.
+            while let Some((&byte, tail)) = rest.split_first() {
+                self.partial.push(byte);
+                rest = tail;
+                match std::str::from_utf8(&self.partial) {
+                    Ok(text) => {
+                        let ch = text.chars().next().unwrap();
+                        self.offset += self.partial.len() as u64;
+                        self.partial.clear();
+                        self.feed_chars([ch]);
+                        break;
+                    }
+                    Err(error) if error.error_len().is_some() => {
+                        return Err(MatchError::InvalidUtf8{offset: self.offset});
+                    }
+                    Err(_) => {}
+                }
+            }
+            if !self.partial.is_empty() {
+                return Ok(self.status());
+            }
+        }
+        match std::str::from_utf8(rest) {
+            Ok(text) => Ok(self.feed_str(text)),
+            Err(error) => {
+                let (valid, tail) = rest.split_at(error.valid_up_to());
+                // The prefix was just checked to be valid UTF-8.
+                self.feed_str(std::str::from_utf8(valid).unwrap());
+                if error.error_len().is_some() {
+                    return Err(MatchError::InvalidUtf8{offset: self.offset});
+                }
+                self.partial.extend_from_slice(tail);
+                Ok(self.status())
+            }
+        }
+    }
+
+    /// Ends the input: fails when it stops in the middle of a character.
+    pub fn finish(&mut self) -> Result<Status, MatchError> {
+        if !self.partial.is_empty() && self.state.is_some() {
+            return Err(MatchError::InvalidUtf8{offset: self.offset});
+        }
+        Ok(self.status())
+    }
+
+    /// Feeds everything `reader` yields and finishes. Reading stops early
+    /// once the input is `Dead`.
+    pub fn feed_reader<R: Read>(&mut self, mut reader: R) -> Result<Status, MatchError> {
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            let len = match reader.read(&mut buffer) {
+                Ok(0) => return self.finish(),
+                Ok(len) => len,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error.into()),
+            };
+            if self.feed_bytes(&buffer[..len])? == Status::Dead {
+                return Ok(Status::Dead);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
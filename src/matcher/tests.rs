@@ -0,0 +1,95 @@
+use std::io::{self, Read};
+
+use crate::dfa::*;
+use crate::matcher::*;
+use crate::testing::*;
+
+fn matcher(pattern: &str) -> Matcher {
+    Matcher::new(&from_regex(pattern))
+}
+
+#[test]
+fn matcher_status_test() {
+    let mut m = matcher("(ab)*c");
+    assert_eq!(m.status(), Status::Possible);
+    assert_eq!(m.feed_str("a"), Status::Possible);
+    assert_eq!(m.feed_str("bab"), Status::Possible);
+    assert_eq!(m.feed_str("c"), Status::Accepted);
+    assert_eq!(m.feed_str("c"), Status::Dead);
+    assert_eq!(m.feed_str("ab"), Status::Dead);
+
+    m.reset();
+    assert_eq!(m.feed_chars("abc".chars()), Status::Accepted);
+
+    let empty = Matcher::new(&Dfa::builder().start(0).state(0, false).build().unwrap());
+    assert_eq!(empty.status(), Status::Dead);
+}
+
+#[test]
+fn matcher_split_utf8_test() {
+    let text = "日本ü日本";
+    let bytes = text.as_bytes();
+    let pattern = "(日本+ü)*";
+    for split in 0..=bytes.len() {
+        let mut m = matcher(pattern);
+        m.feed_bytes(&bytes[..split]).unwrap();
+        assert_eq!(m.feed_bytes(&bytes[split..]).unwrap(), Status::Accepted);
+        assert_eq!(m.finish().unwrap(), Status::Accepted);
+    }
+
+    let mut m = matcher(pattern);
+    for byte in bytes {
+        m.feed_bytes(&[*byte]).unwrap();
+    }
+    assert_eq!(m.finish().unwrap(), Status::Accepted);
+
+    let mut m = matcher(pattern);
+    assert_eq!(m.feed_bytes(&bytes[..4]).unwrap(), Status::Possible);
+    assert!(matches!(m.finish(), Err(MatchError::InvalidUtf8{offset: 3})));
+}
+
+#[test]
+fn matcher_invalid_utf8_test() {
+    let mut m = matcher("a*");
+    let error = m.feed_bytes(b"aaa\xffa").err().unwrap();
+    assert!(matches!(error, MatchError::InvalidUtf8{offset: 3}));
+    assert_eq!(error.to_string(), "invalid UTF-8 at byte 3");
+
+    let mut m = matcher("a*");
+    m.feed_bytes(b"a\xe6\x97").unwrap();
+    assert!(matches!(m.feed_bytes(b"a"), Err(MatchError::InvalidUtf8{offset: 1})));
+}
+
+/// Yields `len` copies of `byte` in reads of at most 1000 bytes.
+struct Repeat {
+    byte: u8,
+    len: usize,
+    reads: usize
+}
+
+impl Read for Repeat {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = buf.len().min(self.len).min(1000);
+        buf[..count].fill(self.byte);
+        self.len -= count;
+        self.reads += 1;
+        Ok(count)
+    }
+}
+
+#[test]
+fn matcher_reader_test() {
+    let mut m = matcher("(aa)*");
+    let mut reader = Repeat{byte: b'a', len: 1_000_000, reads: 0};
+    assert_eq!(m.feed_reader(&mut reader).unwrap(), Status::Accepted);
+
+    m.reset();
+    let mut reader = Repeat{byte: b'b', len: 1_000_000, reads: 0};
+    assert_eq!(m.feed_reader(&mut reader).unwrap(), Status::Dead);
+    assert_eq!(reader.reads, 1);
+
+    m.reset();
+    assert_eq!(m.feed_reader("aaé".as_bytes()).unwrap(), Status::Dead);
+    m.reset();
+    assert_eq!(m.feed_reader(&b"aaa"[..]).unwrap(), Status::Possible);
+}
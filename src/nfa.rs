@@ -1,18 +1,21 @@
-use std::collections::{BTreeMap, VecDeque, BTreeSet};
+use std::collections::{BTreeMap, VecDeque, BTreeSet, HashMap};
 use std::fmt::Display;
 use crate::automaton::*;
 use crate::state::*;
 use crate::dfa::{Dfa, DfaState, ToCompleteDfa, ToMinimalCompleteDfa, ToDfa};
 use std::fmt;
+use crate::text::escape;
+use crate::error::AutomatonError;
 
 #[derive(Clone)]
 pub struct NfaState<T: Traversable + Display + Clone> {
     pub index: usize,
     pub is_terminal: bool,
-    transitions: Vec<(T, usize)>
+    pub(crate) transitions: Vec<(T, usize)>
 }
 
 impl<T: Traversable + Display + Clone> NfaState<T> {
+    #[allow(clippy::redundant_field_names)]
     pub fn new(index: usize, is_terminal: bool) -> Self {
         Self{index: index, is_terminal: is_terminal, transitions: Vec::new()}
     }
@@ -25,6 +28,7 @@ impl<T: Traversable + Display + Clone> NfaState<T> {
 }
 
 impl<T: Traversable + Display + Clone> State for NfaState<T> {
+    #[allow(clippy::clone_on_copy)]
     fn next(&self, str: &str) -> Vec<(usize, String)> {
         let mut result = Vec::new();
         for (word, state) in &self.transitions {
@@ -45,54 +49,158 @@ pub struct Nfa<T: Traversable + Display + Clone> {
 
 impl<T: Traversable + Display + Clone> Nfa<T> {
     pub fn new(starting_state: usize) -> Self {
-        Self{starting_state: starting_state, states: BTreeMap::new()}
+        Self{starting_state, states: BTreeMap::new()}
     }
     
+    pub fn builder() -> NfaBuilder<T> {
+        NfaBuilder{starting_state: None, states: vec![], transitions: vec![]}
+    }
+
+    /// Panics when a state with the same index exists, see `try_add_state`.
     pub fn add_state(&mut self, state: NfaState<T>) {
         if self.states.contains_key(&state.index) {
             panic!("State index duplicate!");
         }
         self.states.insert(state.index, state);
     }
+
+    pub fn try_add_state(&mut self, state: NfaState<T>) -> Result<(), AutomatonError> {
+        if self.states.contains_key(&state.index) {
+            return Err(AutomatonError::DuplicateState(state.index));
+        }
+        self.states.insert(state.index, state);
+        Ok(())
+    }
+
+    /// Checks that the starting state and the ends of every transition
+    /// exist, which the other methods assume.
+    pub fn validate(&self) -> Result<(), AutomatonError> {
+        if !self.states.contains_key(&self.starting_state) {
+            return Err(AutomatonError::UnknownStartState(self.starting_state));
+        }
+        for state in self.states.values() {
+            for (_, next_state) in state.transitions.iter() {
+                if !self.states.contains_key(next_state) {
+                    return Err(AutomatonError::DanglingTransition{from: state.index, to: *next_state});
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Collects states and transitions in any order and checks them in `build`.
+#[derive(Clone)]
+pub struct NfaBuilder<T: Traversable + Display + Clone> {
+    starting_state: Option<usize>,
+    states: Vec<(usize, bool)>,
+    transitions: Vec<(usize, T, usize)>
+}
+
+impl<T: Traversable + Display + Clone> NfaBuilder<T> {
+    pub fn start(mut self, idx: usize) -> Self {
+        self.starting_state = Some(idx);
+        self
+    }
+
+    pub fn state(mut self, idx: usize, is_terminal: bool) -> Self {
+        self.states.push((idx, is_terminal));
+        self
+    }
+
+    pub fn transition(mut self, from: usize, word: T, to: usize) -> Self {
+        self.transitions.push((from, word, to));
+        self
+    }
+
+    pub fn build(self) -> Result<Nfa<T>, AutomatonError> {
+        let starting_state = self.starting_state.ok_or(AutomatonError::MissingStartState)?;
+        let mut nfa = Nfa::new(starting_state);
+        for (idx, is_terminal) in self.states {
+            nfa.try_add_state(NfaState::new(idx, is_terminal))?;
+        }
+        if !nfa.states.contains_key(&starting_state) {
+            return Err(AutomatonError::UnknownStartState(starting_state));
+        }
+        for (from, word, to) in self.transitions {
+            if !nfa.states.contains_key(&to) {
+                return Err(AutomatonError::DanglingTransition{from, to});
+            }
+            let Some(state) = nfa.states.get_mut(&from) else {
+                return Err(AutomatonError::DanglingTransition{from, to});
+            };
+            state.add_transition(word, to);
+        }
+        Ok(nfa)
+    }
 }
 
 impl<T: Traversable + Display + Clone> Automaton<'_> for Nfa<T> {
-    fn accept<'a>(&self, str: &'a str) -> bool {
-        let mut counter = (str.len() + 1) * (self.states.len() + 1) * (self.states.len() + 1);
-        let mut queue: VecDeque<(usize, String)> = VecDeque::new();
-        queue.push_back((self.starting_state, str.to_string()));
-        while counter > 0 && !queue.is_empty() {
-            counter -= 1;
-            let (state, word) = queue.pop_front().unwrap();
-            if word.len() == 0 && self.states[&state].is_terminal {
-                return true
+    /// Simulates all runs at once. The states reached at each byte position
+    /// of the input are visited once, positions in increasing order, so the
+    /// running time is bounded by the input length times the total length of
+    /// the transition labels. Labels are matched in place with `go`, which
+    /// also covers empty and multi-character words.
+    fn accept(&self, str: &str) -> bool {
+        let states: Vec<&NfaState<T>> = self.states.values().collect();
+        let dense: HashMap<usize, usize> = states.iter().enumerate().map(|(i, state)| (state.index, i)).collect();
+        let Some(&start) = dense.get(&self.starting_state) else {
+            return false;
+        };
+
+        // `pending[pos]` holds states entered at byte offset `pos`;
+        // `visited[s]` is the last offset at which `s` was expanded.
+        let mut pending: BTreeMap<usize, Vec<usize>> = BTreeMap::from([(0, vec![start])]);
+        let mut visited = vec![usize::MAX; states.len()];
+        while let Some((pos, entered)) = pending.pop_first() {
+            let rest = &str[pos..];
+            let mut stack = vec![];
+            for s in entered {
+                if visited[s] != pos {
+                    visited[s] = pos;
+                    stack.push(s);
+                }
             }
-            for (next_state, next_word) in self.states[&state].next(&word) {
-                queue.push_back((next_state, next_word));
+            while let Some(s) = stack.pop() {
+                if rest.is_empty() && states[s].is_terminal {
+                    return true;
+                }
+                for (word, next_state) in states[s].transitions.iter() {
+                    let (Some(&t), Some(remaining)) = (dense.get(next_state), word.go(rest)) else {
+                        continue;
+                    };
+                    let next_pos = str.len() - remaining.len();
+                    if next_pos > pos {
+                        pending.entry(next_pos).or_default().push(t);
+                    } else if visited[t] != pos {
+                        visited[t] = pos;
+                        stack.push(t);
+                    }
+                }
             }
         }
-        return false
+        false
     }
 }
 
 impl Nfa<String> {
     pub fn compress_eps(&self) -> Self {
         let mut result = self.clone();
-        for (idx, _) in self.states.iter() {
+        for idx in self.states.keys() {
             let mut new_transitions = vec![];
             let mut is_terminal = false;
-            let n: usize = *self.states.keys().next_back().unwrap() as usize + 1;
+            let n: usize = *self.states.keys().next_back().unwrap() + 1;
             let mut used = vec![false; n];
             let mut queue: VecDeque<usize> = VecDeque::new();
             queue.push_back(*idx);
             while !queue.is_empty() {
                 let cur = queue.pop_front().unwrap();
                 is_terminal |= result.states[&cur].is_terminal;
-                used[cur as usize] = true;
+                used[cur] = true;
                 for (word, next_state) in self.states[&cur].transitions.iter() {
-                    if word.len() > 0 {
+                    if !word.is_empty() {
                         new_transitions.push((word.clone(), *next_state));
-                    } else if !used[*next_state as usize] {
+                    } else if !used[*next_state] {
                         queue.push_back(*next_state);
                     }
                 }
@@ -111,24 +219,25 @@ impl Nfa<String> {
             result.add_state(NfaState::new(*idx, state.is_terminal));
         }
 
-        for (idx, _) in nfa.states.iter() {
-            for (word, next_state) in nfa.states[&idx].transitions.iter() {
-                if word.len() == 1 {
+        for idx in nfa.states.keys() {
+            for (word, next_state) in nfa.states[idx].transitions.iter() {
+                let chars: Vec<char> = word.chars().collect();
+                if chars.len() == 1 {
                     result.states.get_mut(idx)
                                  .unwrap()
-                                 .add_transition(word.chars().next().unwrap(), *next_state);
+                                 .add_transition(chars[0], *next_state);
                 } else {
-                    for i in 0..word.len() - 1 {
-                        let new_idx = *result.states.keys().next_back().unwrap() + 1;
-                        result.states.get_mut(idx)
-                                     .unwrap()
-                                     .add_transition(word.chars().nth(i).unwrap(), new_idx);
-                        result.add_state(NfaState::new(new_idx, false));
-                        if i == word.len() - 2 {
-                            result.states.get_mut(&new_idx)
-                                         .unwrap()
-                                         .add_transition(word.chars().nth(word.len() - 1).unwrap(), *next_state);
-                        }
+                    let mut cur = *idx;
+                    for (i, ch) in chars.iter().enumerate() {
+                        let new_idx = if i == chars.len() - 1 {
+                            *next_state
+                        } else {
+                            let new_idx = *result.states.keys().next_back().unwrap() + 1;
+                            result.add_state(NfaState::new(new_idx, false));
+                            new_idx
+                        };
+                        result.states.get_mut(&cur).unwrap().add_transition(*ch, new_idx);
+                        cur = new_idx;
                     }
                 }
             }
@@ -138,54 +247,90 @@ impl Nfa<String> {
     }
 }
 
-impl ToDfa for Nfa<char> {
-    fn to_dfa(&self) -> Dfa {
-        let mut dfa = Dfa::new(1 << self.starting_state);
-        let mut queue: VecDeque<usize> = VecDeque::new();
-        let mut alphabet: BTreeSet<char> = BTreeSet::new();
-        let n: usize = *self.states.keys().next_back().unwrap() as usize + 1;
-        for (_, state) in self.states.iter() {
-            for (ch, _) in state.transitions.iter() {
-                alphabet.insert(*ch);
-            }
-        }
-        queue.push_back(1 << self.starting_state);
-        while !queue.is_empty() {
-            let cur = queue.pop_back().unwrap();
-            if dfa.states.contains_key(&cur) {
-                continue;
-            }
+/// Outcome of the subset construction. DFA states are numbered densely in
+/// the order of their subsets compared from the largest NFA state down, as
+/// the former bitmask encoding sorted them, so the starting state need not
+/// be 0. `subsets[i]` holds the sorted NFA states that DFA state `i` stands
+/// for.
+#[derive(Clone)]
+pub struct Determinization {
+    pub dfa: Dfa,
+    pub subsets: Vec<Vec<usize>>
+}
+
+impl Nfa<char> {
+    pub fn determinize(&self) -> Determinization {
+        let mut subsets: Vec<Vec<usize>> = vec![vec![self.starting_state]];
+        let mut index: HashMap<Vec<usize>, usize> = HashMap::new();
+        index.insert(subsets[0].clone(), 0);
+
+        let mut found: Vec<DfaState> = vec![];
+        while found.len() < subsets.len() {
+            let cur = found.len();
             let mut is_terminal = false;
-            for i in 0..n {
-                if (cur >> i & 1) == 0 {
-                    continue;
+            let mut moves: BTreeMap<char, BTreeSet<usize>> = BTreeMap::new();
+            for i in subsets[cur].iter() {
+                is_terminal |= self.states[i].is_terminal;
+                for (ch, next_state) in self.states[i].transitions.iter() {
+                    moves.entry(*ch).or_default().insert(*next_state);
                 }
-                is_terminal |= self.states[&i].is_terminal;
             }
             let mut cur_state = DfaState::new(cur, is_terminal);
-            for ch in alphabet.iter() {
-                let mut q = 0;
-                for i in 0..n {
-                    if (cur >> i & 1) == 0 {
-                        continue;
-                    }
-                    for (transition, idx) in self.states[&i].transitions.iter() {
-                        if transition == ch {
-                            q |= 1 << idx;
-                        }
+            for (ch, set) in moves {
+                let set: Vec<usize> = set.into_iter().collect();
+                let next = match index.get(&set) {
+                    Some(next) => *next,
+                    None => {
+                        let next = subsets.len();
+                        index.insert(set.clone(), next);
+                        subsets.push(set);
+                        next
                     }
-                }
-                if q == 0 {
-                    continue;
-                }
-                cur_state.add_transition(*ch, q);
-                if !dfa.states.contains_key(&q) {
-                    queue.push_back(q);
-                }
+                };
+                cur_state.add_transition(ch, next);
+            }
+            found.push(cur_state);
+        }
+
+        // Number the states in the order the former bitmask encoding sorted
+        // them, so that state-order dependent output like `to_regex` is stable.
+        let mut order: Vec<usize> = (0..subsets.len()).collect();
+        order.sort_by(|a, b| subsets[*a].iter().rev().cmp(subsets[*b].iter().rev()));
+        let mut rank = vec![0; order.len()];
+        for (new_idx, old_idx) in order.iter().enumerate() {
+            rank[*old_idx] = new_idx;
+        }
+
+        let mut dfa = Dfa::new(rank[0]);
+        for old_idx in order.iter() {
+            let mut state = DfaState::new(rank[*old_idx], found[*old_idx].is_terminal);
+            for (ch, next_state) in found[*old_idx].transitions().iter() {
+                state.add_transition(*ch, rank[*next_state]);
             }
-            dfa.add_state(cur_state);
+            dfa.add_state(state);
         }
-        dfa
+        let subsets = order.into_iter().map(|i| std::mem::take(&mut subsets[i])).collect();
+        Determinization{dfa, subsets}
+    }
+}
+
+impl ToDfa for Nfa<char> {
+    fn to_dfa(&self) -> Dfa {
+        self.determinize().dfa
+    }
+}
+
+impl Nfa<String> {
+    pub fn shortest_accepted(&self) -> Option<String> {
+        self.to_dfa().shortest_accepted()
+    }
+
+    pub fn shortest_rejected(&self, alphabet: &BTreeSet<char>) -> Option<String> {
+        self.to_dfa().shortest_rejected(alphabet)
+    }
+
+    pub fn longest_accepted(&self) -> Option<String> {
+        self.to_dfa().longest_accepted()
     }
 }
 
@@ -226,7 +371,7 @@ impl<T: Traversable + Display + Clone> fmt::Display for Nfa<T> {
         writeln!(f, "Starting state: {}", self.starting_state)?;
         for (idx, state) in self.states.iter() {
             for (word, next_state) in state.transitions.iter() {
-                writeln!(f, "{idx} -> {next_state} by \"{word}\"")?;
+                writeln!(f, "{idx} -> {next_state} by \"{}\"", escape(&word.to_string()))?;
             }
         }
         writeln!(f, "Terminal states:")?;
@@ -239,10 +384,11 @@ impl<T: Traversable + Display + Clone> fmt::Display for Nfa<T> {
     }
 }
 
+#[allow(clippy::for_kv_map, clippy::map_entry, clippy::single_char_add_str, clippy::len_zero, clippy::needless_borrow, clippy::get_first)]
 impl ToRegex for Nfa<String> {
     fn to_regex(&self) -> String {
         let mut new_nfa = self.to_dfa().to_nfa();
-        let max = new_nfa.states.keys().next_back().unwrap().clone();
+        let max = new_nfa.states.keys().next_back().copied().unwrap_or(0);
         
         let mut new_start: NfaState<String> = NfaState::new(max + 1, false);
         new_start.add_transition(String::from(""), new_nfa.starting_state);
@@ -310,9 +456,13 @@ impl ToRegex for Nfa<String> {
                 for (k, (word, idx)) in new_nfa.states[&j].transitions.iter().enumerate() {
                     if *idx == i {
                         for (second_word, final_state) in new_nfa.states[&i].transitions.iter() {
-                            let mut transition = word.clone();
-                            transition.push_str(&self_loop);
-                            transition.push_str(second_word);
+                            let transition = if word.is_empty() && self_loop.is_empty() {
+                                second_word.clone()
+                            } else if self_loop.is_empty() && second_word.is_empty() {
+                                word.clone()
+                            } else {
+                                parenthesize_union(word) + &self_loop + &parenthesize_union(second_word)
+                            };
                             if new_transitions.contains_key(final_state) {
                                 new_transitions.get_mut(final_state).unwrap().push(transition);
                             } else {
@@ -343,7 +493,7 @@ impl ToRegex for Nfa<String> {
                     }
                 }
                 for (next_state, words) in new_transitions.iter() {
-                    new_nfa.states.get_mut(&j).unwrap().add_transition(words.join(""), *next_state);
+                    new_nfa.states.get_mut(&j).unwrap().add_transition(words.join("+"), *next_state);
                 }
             }
             new_nfa.states.remove(&i);
@@ -353,6 +503,21 @@ impl ToRegex for Nfa<String> {
     }
 }
 
+/// Wraps `word` in parentheses when it is a union at the top level, so that
+/// it can be concatenated with other words without changing its meaning.
+fn parenthesize_union(word: &str) -> String {
+    let mut depth = 0;
+    for ch in word.chars() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '+' if depth == 0 => return format!("({word})"),
+            _ => {}
+        }
+    }
+    word.to_string()
+}
+
 impl Nfa<char> {
     fn to_nfa_string(&self) -> Nfa<String> {
         let mut nfa: Nfa<String> = Nfa::new(self.starting_state);
@@ -1,7 +1,12 @@
+use std::collections::BTreeSet;
+
 use crate::automaton::*;
+use crate::error::AutomatonError;
 use crate::nfa::*;
+use crate::testing::*;
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn nfa_base_test() {
     let mut q0: NfaState<String> = NfaState::new(0, true);
     q0.add_transition(String::from("ab"), 0);
@@ -25,6 +30,7 @@ fn nfa_base_test() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn nfa_eps_cycle_test() {
     let mut q0: NfaState<String> = NfaState::new(0, false);
     q0.add_transition(String::from(""), 1);
@@ -52,6 +58,7 @@ fn nfa_eps_cycle_test() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn nfa_to_dfa_test() {
     let mut q0: NfaState<String> = NfaState::new(0, false);
     q0.add_transition(String::from("a"), 0);
@@ -107,6 +114,7 @@ fn nfa_to_dfa_size_test() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn nfa_to_mcdfa() {
     let mut q0: NfaState<char> = NfaState::new(0, false);
     q0.add_transition('a', 1);
@@ -194,4 +202,158 @@ fn duplicate_index_test() {
     let mut nfa: Nfa<char> = Nfa::new(0);
     nfa.add_state(q0);
     nfa.add_state(q1);
-}
\ No newline at end of file
+}
+
+#[test]
+fn nfa_to_dfa_many_states_test() {
+    let mut nfa: Nfa<char> = Nfa::new(0);
+    for i in 0..200 {
+        let mut state: NfaState<char> = NfaState::new(i, i == 199);
+        state.add_transition('a', i + 1);
+        state.add_transition('b', i);
+        nfa.add_state(state);
+    }
+    nfa.add_state(NfaState::new(200, false));
+
+    let determinization = nfa.determinize();
+    let dfa = &determinization.dfa;
+
+    assert_eq!(dfa.states.len(), 201);
+    assert_eq!(determinization.subsets[dfa.starting_state], vec![0]);
+    assert!(dfa.accept(&"a".repeat(199)));
+    assert!(dfa.accept(&"ab".repeat(199)));
+    assert!(!dfa.accept(&"a".repeat(198)));
+    assert!(!dfa.accept(&"a".repeat(200)));
+}
+
+#[test]
+fn nfa_determinize_subsets_test() {
+    let mut q0: NfaState<char> = NfaState::new(0, false);
+    q0.add_transition('a', 0);
+    q0.add_transition('a', 1);
+
+    let q1: NfaState<char> = NfaState::new(1, true);
+
+    let mut nfa: Nfa<char> = Nfa::new(0);
+    nfa.add_state(q0);
+    nfa.add_state(q1);
+
+    let determinization = nfa.determinize();
+    let dfa = &determinization.dfa;
+
+    assert_eq!(dfa.states.len(), 2);
+    for (idx, state) in dfa.states.iter() {
+        assert_eq!(state.is_terminal, determinization.subsets[*idx] == vec![0, 1]);
+    }
+}
+
+#[test]
+fn nfa_witness_test() {
+    let ab = BTreeSet::from(['a', 'b']);
+
+    let nfa = from_regex("(ab+b)(a+ba)");
+    assert_eq!(nfa.shortest_accepted(), Some(String::from("ba")));
+    assert_eq!(nfa.shortest_rejected(&ab), Some(String::from("")));
+    assert_eq!(nfa.longest_accepted(), Some(String::from("abba")));
+
+    let nfa = from_regex("+(a+b)(a+b)*");
+    assert_eq!(nfa.shortest_accepted(), Some(String::from("")));
+    assert_eq!(nfa.shortest_rejected(&ab), None);
+    assert_eq!(nfa.shortest_rejected(&BTreeSet::from(['a', 'c'])), Some(String::from("c")));
+    assert_eq!(nfa.longest_accepted(), None);
+
+    let nfa = from_regex("a*b");
+    assert_eq!(nfa.shortest_rejected(&ab), Some(String::from("")));
+    assert_eq!(nfa.to_dfa().complement().shortest_rejected(&ab), Some(String::from("b")));
+}
+
+#[test]
+fn nfa_builder_test() {
+    let nfa: Nfa<String> = Nfa::builder()
+        .transition(0, String::from("ab"), 1)
+        .state(1, true)
+        .state(0, false)
+        .transition(1, String::from(""), 0)
+        .start(0)
+        .build()
+        .unwrap();
+    assert!(nfa.validate().is_ok());
+    assert!(nfa.accept("abab"));
+    assert!(!nfa.accept("a"));
+
+    let builder: NfaBuilder<String> = Nfa::builder().state(0, false).state(1, true);
+    assert_eq!(builder.clone().build().err().unwrap(), AutomatonError::MissingStartState);
+    assert_eq!(builder.clone().start(2).build().err().unwrap(), AutomatonError::UnknownStartState(2));
+    assert_eq!(builder.clone().state(1, false).start(0).build().err().unwrap(),
+               AutomatonError::DuplicateState(1));
+    assert_eq!(builder.clone().start(0).transition(0, String::from("a"), 3).build().err().unwrap(),
+               AutomatonError::DanglingTransition{from: 0, to: 3});
+    assert_eq!(builder.start(0).transition(4, String::from("a"), 1).build().err().unwrap(),
+               AutomatonError::DanglingTransition{from: 4, to: 1});
+}
+
+#[test]
+fn nfa_validate_test() {
+    let mut nfa: Nfa<char> = Nfa::new(1);
+    let mut q0 = NfaState::new(0, false);
+    q0.add_transition('a', 2);
+    nfa.add_state(q0);
+    assert_eq!(nfa.validate().err().unwrap(), AutomatonError::UnknownStartState(1));
+    assert!(nfa.try_add_state(NfaState::new(1, true)).is_ok());
+    assert_eq!(nfa.try_add_state(NfaState::new(1, true)).err().unwrap(), AutomatonError::DuplicateState(1));
+    assert_eq!(nfa.validate().err().unwrap(), AutomatonError::DanglingTransition{from: 0, to: 2});
+}
+
+#[test]
+fn split_words_test() {
+    let nfa: Nfa<String> = Nfa::builder()
+        .start(0)
+        .state(0, false)
+        .state(1, true)
+        .transition(0, String::from("abc"), 1)
+        .transition(1, String::from("名前"), 1)
+        .build()
+        .unwrap();
+    let split = nfa.split_words();
+    assert_eq!(split.states.len(), 5);
+    assert!(split.accept("abc名前名前"));
+    assert!(!split.accept("bc"));
+    assert!(!split.accept("ac"));
+    assert!(nfa.to_dfa().accept("abc名前"));
+    assert!(!nfa.to_dfa().accept("abc名"));
+}
+
+#[test]
+fn nfa_accept_epsilon_chain_test() {
+    // 0 -ε-> 1 -ε-> ... -ε-> 40 -a-> 0, every state also loops back to 0 by ε.
+    let mut builder: NfaBuilder<String> = Nfa::builder().start(0).state(40, true);
+    for i in 0..40 {
+        builder = builder.state(i, false)
+                         .transition(i, String::new(), i + 1)
+                         .transition(i + 1, String::new(), 0);
+    }
+    let nfa = builder.transition(40, String::from("a"), 0).build().unwrap();
+    let word = "a".repeat(200);
+    assert!(nfa.accept(&word));
+    assert!(!nfa.accept(&format!("{word}b")));
+}
+
+#[test]
+fn nfa_accept_matches_dfa_test() {
+    let nfa = from_regex("(ab+a(ba)*+)*(b+é)");
+    let dfa = nfa.to_dfa();
+    for word in words(&['a', 'b', 'é'], 7) {
+        assert_eq!(nfa.accept(&word), dfa.accept(&word), "{word}");
+    }
+
+    let mut q0: NfaState<String> = NfaState::new(0, false);
+    q0.add_transition(String::from("ab"), 1);
+    q0.add_transition(String::from("abab"), 2);
+    let mut nfa: Nfa<String> = Nfa::new(0);
+    nfa.add_state(q0);
+    nfa.add_state(NfaState::new(1, false));
+    nfa.add_state(NfaState::new(2, true));
+    assert!(nfa.accept("abab"));
+    assert!(!nfa.accept("ab"));
+    assert!(!Nfa::<char>::new(3).accept(""));
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::dfa::{Dfa, DfaState};
+use crate::nfa::{Nfa, NfaState};
+
+/// Error of the text based readers, with 1-based line and column of the
+/// offending input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: &str) -> Self {
+        Self{line, column, message: message.to_string()}
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Transition as read by one of the readers, with the position it was read
+/// from.
+pub(crate) struct RawTransition {
+    pub from: usize,
+    pub word: String,
+    pub to: usize,
+    pub line: usize,
+    pub column: usize
+}
+
+/// Automaton as read from text, before the labels are checked against the
+/// automaton type that is being built. Every state used by a transition is
+/// present in `states`.
+pub(crate) struct RawAutomaton {
+    pub starting_state: usize,
+    pub states: BTreeMap<usize, bool>,
+    pub transitions: Vec<RawTransition>
+}
+
+impl RawAutomaton {
+    pub fn into_nfa(self) -> Nfa<String> {
+        let mut nfa: Nfa<String> = Nfa::new(self.starting_state);
+        for (idx, is_terminal) in self.states {
+            nfa.add_state(NfaState::new(idx, is_terminal));
+        }
+        for transition in self.transitions {
+            nfa.states.get_mut(&transition.from).unwrap().add_transition(transition.word, transition.to);
+        }
+        nfa
+    }
+
+    pub fn into_char_nfa(self) -> Result<Nfa<char>, ParseError> {
+        let mut nfa: Nfa<char> = Nfa::new(self.starting_state);
+        for (idx, is_terminal) in self.states {
+            nfa.add_state(NfaState::new(idx, is_terminal));
+        }
+        for transition in self.transitions {
+            let ch = transition.symbol()?;
+            nfa.states.get_mut(&transition.from).unwrap().add_transition(ch, transition.to);
+        }
+        Ok(nfa)
+    }
+
+    /// Every label must be a single symbol and no state may have two
+    /// transitions on the same symbol.
+    pub fn into_dfa(self) -> Result<Dfa, ParseError> {
+        let mut dfa = Dfa::new(self.starting_state);
+        for (idx, is_terminal) in self.states {
+            dfa.add_state(DfaState::new(idx, is_terminal));
+        }
+        for transition in self.transitions {
+            let ch = transition.symbol()?;
+            let state = dfa.states.get_mut(&transition.from).unwrap();
+            if state.next_state(ch).is_some() {
+                return Err(ParseError::new(transition.line, transition.column,
+                                           &format!("second transition on '{ch}'")));
+            }
+            state.add_transition(ch, transition.to);
+        }
+        Ok(dfa)
+    }
+}
+
+impl RawTransition {
+    fn symbol(&self) -> Result<char, ParseError> {
+        let mut chars = self.word.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => Ok(ch),
+            _ => Err(ParseError::new(self.line, self.column,
+                                     &format!("label \"{}\" is not a single symbol", self.word))),
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::nfa::{Nfa, NfaState};
+use crate::tnfa::{Tnfa, TnfaLabel, TnfaState};
+
+/// Regular expression in the syntax produced by `ToRegex::to_regex`:
+/// `+` is union, `*` is Kleene star, juxtaposition is concatenation and
+/// an empty alternative (as in `(+a)`) stands for the empty word.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Regex {
+    Epsilon,
+    Symbol(char),
+    Concat(Vec<Regex>),
+    Union(Vec<Regex>),
+    Star(Box<Regex>),
+    Group(Box<Regex>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegexError {
+    pub position: usize,
+    pub message: String
+}
+
+impl RegexError {
+    fn new(position: usize, message: &str) -> Self {
+        Self{position, message: message.to_string()}
+    }
+}
+
+impl fmt::Display for RegexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for RegexError {}
+
+/// Deepest nesting of parentheses `Regex::parse` accepts; the parser and
+/// the constructions over the result recurse once per level.
+pub const MAX_NESTING: usize = 200;
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn parse_union(&mut self) -> Result<Regex, RegexError> {
+        let mut alternatives = vec![self.parse_concat()?];
+        while self.peek() == Some('+') {
+            self.pos += 1;
+            alternatives.push(self.parse_concat()?);
+        }
+        if alternatives.len() == 1 {
+            return Ok(alternatives.pop().unwrap());
+        }
+        Ok(Regex::Union(alternatives))
+    }
+
+    fn parse_concat(&mut self) -> Result<Regex, RegexError> {
+        let mut items = vec![];
+        while let Some(ch) = self.peek() {
+            if ch == '+' || ch == ')' {
+                break;
+            }
+            items.push(self.parse_star()?);
+        }
+        match items.len() {
+            0 => Ok(Regex::Epsilon),
+            1 => Ok(items.pop().unwrap()),
+            _ => Ok(Regex::Concat(items)),
+        }
+    }
+
+    fn parse_star(&mut self) -> Result<Regex, RegexError> {
+        let mut result = self.parse_atom()?;
+        if self.peek() == Some('*') {
+            // `r**` is `r*`, so a run of stars adds a single level.
+            while self.peek() == Some('*') {
+                self.pos += 1;
+            }
+            result = Regex::Star(Box::new(result));
+        }
+        Ok(result)
+    }
+
+    fn parse_atom(&mut self) -> Result<Regex, RegexError> {
+        let start = self.pos;
+        match self.peek() {
+            Some('(') => {
+                if self.depth == MAX_NESTING {
+                    return Err(RegexError::new(start, "parentheses nested too deeply"));
+                }
+                self.pos += 1;
+                self.depth += 1;
+                let inner = self.parse_union()?;
+                self.depth -= 1;
+                if self.peek() != Some(')') {
+                    return Err(RegexError::new(start, "unclosed '('"));
+                }
+                self.pos += 1;
+                Ok(Regex::Group(Box::new(inner)))
+            }
+            Some('*') => Err(RegexError::new(start, "'*' has nothing to repeat")),
+            Some(ch) => {
+                self.pos += 1;
+                Ok(Regex::Symbol(ch))
+            }
+            None => Err(RegexError::new(start, "unexpected end of pattern")),
+        }
+    }
+}
+
+impl Regex {
+    pub fn parse(pattern: &str) -> Result<Regex, RegexError> {
+        let mut parser = Parser{chars: pattern.chars().collect(), pos: 0, depth: 0};
+        let result = parser.parse_union()?;
+        if parser.pos < parser.chars.len() {
+            return Err(RegexError::new(parser.pos, "unmatched ')'"));
+        }
+        Ok(result)
+    }
+
+    /// Thompson construction: every sub-expression becomes a fragment with
+    /// one entry and one exit state, glued together by empty-string
+    /// transitions. The only terminal state is the exit of the whole regex.
+    pub fn to_nfa(&self) -> Nfa<String> {
+        let mut builder = Thompson{states: vec![], groups: None, open: vec![], parents: vec![]};
+        let (start, end) = builder.build(self);
+
+        let mut nfa: Nfa<String> = Nfa::new(start);
+        for (idx, transitions) in builder.states.into_iter().enumerate() {
+            let mut state = NfaState::new(idx, idx == end);
+            for (label, next_state) in transitions {
+                if let TnfaLabel::Word(word) = label {
+                    state.add_transition(word, next_state);
+                }
+            }
+            nfa.add_state(state);
+        }
+        nfa
+    }
+
+    /// Like `to_nfa`, but every parenthesized group records where it starts
+    /// and ends. Groups are numbered from 1 in the order of their opening
+    /// parentheses; group 0 is the whole match.
+    pub fn to_tnfa(&self) -> Tnfa {
+        let mut builder = Thompson{states: vec![], groups: Some(0), open: vec![], parents: vec![]};
+        let (start, end) = builder.build(self);
+
+        let mut tnfa = Tnfa::new(start, builder.groups.unwrap() + 1);
+        for (group, parent) in builder.parents.into_iter().enumerate() {
+            tnfa.set_parent(group + 1, parent);
+        }
+        for (idx, transitions) in builder.states.into_iter().enumerate() {
+            let mut state = TnfaState::new(idx, idx == end);
+            for (label, next_state) in transitions {
+                state.add_transition(label, next_state);
+            }
+            tnfa.add_state(state);
+        }
+        tnfa
+    }
+}
+
+struct Thompson {
+    states: Vec<Vec<(TnfaLabel, usize)>>,
+    /// Number of groups seen so far, `None` when groups are not tagged.
+    groups: Option<usize>,
+    /// Groups whose parentheses are open at the current point.
+    open: Vec<usize>,
+    /// Enclosing group of each group from 1 on, 0 for the outermost ones.
+    parents: Vec<usize>
+}
+
+impl Thompson {
+    fn new_state(&mut self) -> usize {
+        self.states.push(vec![]);
+        self.states.len() - 1
+    }
+
+    fn link(&mut self, from: usize, word: &str, to: usize) {
+        self.states[from].push((TnfaLabel::Word(word.to_string()), to));
+    }
+
+    fn build(&mut self, regex: &Regex) -> (usize, usize) {
+        match regex {
+            Regex::Epsilon => {
+                let (start, end) = (self.new_state(), self.new_state());
+                self.link(start, "", end);
+                (start, end)
+            }
+            Regex::Symbol(ch) => {
+                let (start, end) = (self.new_state(), self.new_state());
+                self.link(start, &ch.to_string(), end);
+                (start, end)
+            }
+            Regex::Concat(items) => {
+                let (start, mut end) = self.build(&items[0]);
+                for item in &items[1..] {
+                    let (next_start, next_end) = self.build(item);
+                    self.link(end, "", next_start);
+                    end = next_end;
+                }
+                (start, end)
+            }
+            Regex::Union(alternatives) => {
+                let (start, end) = (self.new_state(), self.new_state());
+                for alternative in alternatives {
+                    let (inner_start, inner_end) = self.build(alternative);
+                    self.link(start, "", inner_start);
+                    self.link(inner_end, "", end);
+                }
+                (start, end)
+            }
+            Regex::Star(inner) => {
+                let (start, end) = (self.new_state(), self.new_state());
+                let (inner_start, inner_end) = self.build(inner);
+                self.link(start, "", inner_start);
+                self.link(start, "", end);
+                self.link(inner_end, "", inner_start);
+                self.link(inner_end, "", end);
+                (start, end)
+            }
+            Regex::Group(inner) => match self.groups {
+                Some(count) => {
+                    let group = count + 1;
+                    self.groups = Some(group);
+                    self.parents.push(self.open.last().copied().unwrap_or(0));
+                    self.open.push(group);
+                    let (start, end) = (self.new_state(), self.new_state());
+                    let (inner_start, inner_end) = self.build(inner);
+                    self.open.pop();
+                    self.states[start].push((TnfaLabel::Tag(2 * group), inner_start));
+                    self.states[inner_end].push((TnfaLabel::Tag(2 * group + 1), end));
+                    (start, end)
+                }
+                None => self.build(inner),
+            },
+        }
+    }
+}
+
+impl FromStr for Regex {
+    type Err = RegexError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        Regex::parse(pattern)
+    }
+}
+
+impl fmt::Display for Regex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Regex::Epsilon => Ok(()),
+            Regex::Symbol(ch) => write!(f, "{ch}"),
+            Regex::Concat(items) => {
+                for item in items {
+                    match item {
+                        Regex::Union(_) => write!(f, "({item})")?,
+                        _ => write!(f, "{item}")?,
+                    }
+                }
+                Ok(())
+            }
+            Regex::Union(alternatives) => {
+                for (i, alternative) in alternatives.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "+")?;
+                    }
+                    write!(f, "{alternative}")?;
+                }
+                Ok(())
+            }
+            Regex::Star(inner) => match inner.as_ref() {
+                Regex::Symbol(_) | Regex::Group(_) | Regex::Star(_) => write!(f, "{inner}*"),
+                _ => write!(f, "({inner})*"),
+            },
+            Regex::Group(inner) => write!(f, "({inner})"),
+        }
+    }
+}
+
+impl Nfa<String> {
+    pub fn from_regex(pattern: &str) -> Result<Self, RegexError> {
+        Ok(Regex::parse(pattern)?.to_nfa())
+    }
+}
+
+#[cfg(test)]
+mod tests;
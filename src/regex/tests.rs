@@ -0,0 +1,92 @@
+use crate::automaton::*;
+use crate::nfa::*;
+use crate::regex::*;
+use crate::testing::*;
+
+#[test]
+fn regex_parse_test() {
+    assert_eq!(Regex::parse("ab").unwrap(),
+               Regex::Concat(vec![Regex::Symbol('a'), Regex::Symbol('b')]));
+    assert_eq!(Regex::parse("a+b").unwrap(),
+               Regex::Union(vec![Regex::Symbol('a'), Regex::Symbol('b')]));
+    assert_eq!(Regex::parse("a*").unwrap(), Regex::Star(Box::new(Regex::Symbol('a'))));
+    assert_eq!(Regex::parse("").unwrap(), Regex::Epsilon);
+    assert_eq!(Regex::parse("+a").unwrap(),
+               Regex::Union(vec![Regex::Epsilon, Regex::Symbol('a')]));
+}
+
+#[test]
+fn regex_display_round_trip_test() {
+    for pattern in ["(b)*a((a+b))*", "bb+(a+ba)(a)*b", "(+a)b", "()*", "a*", "a(b+c)*d"] {
+        assert_eq!(Regex::parse(pattern).unwrap().to_string(), pattern);
+    }
+}
+
+#[test]
+fn regex_error_test() {
+    assert_eq!(Regex::parse("(ab").unwrap_err().position, 0);
+    assert_eq!(Regex::parse("ab)").unwrap_err().position, 2);
+    assert_eq!(Regex::parse("a+*").unwrap_err().position, 2);
+    assert_eq!(Regex::parse("a(b(c)").unwrap_err().position, 1);
+}
+
+#[test]
+fn regex_repeated_star_test() {
+    assert_eq!(Regex::parse("a**").unwrap(), Regex::Star(Box::new(Regex::Symbol('a'))));
+    assert_eq!(Regex::parse("a**").unwrap().to_string(), "a*");
+
+    let nfa = from_regex(&format!("a{}", "*".repeat(200_000)));
+    assert!(nfa.accept("aaa"));
+}
+
+#[test]
+fn regex_nesting_limit_test() {
+    let nested = |depth: usize| format!("{}a{}", "(".repeat(depth), ")".repeat(depth));
+
+    let nfa = from_regex(&nested(MAX_NESTING));
+    assert!(nfa.accept("a"));
+    assert_eq!(Regex::parse(&nested(MAX_NESTING + 1)).unwrap_err().position, MAX_NESTING);
+    assert_eq!(Regex::parse(&"(".repeat(5000)).unwrap_err().position, MAX_NESTING);
+}
+
+#[test]
+fn regex_to_nfa_test() {
+    let nfa = from_regex("(b)*a((a+b))*");
+
+    assert!(nfa.accept("a"));
+    assert!(nfa.accept("ba"));
+    assert!(nfa.accept("bbaab"));
+    assert!(!nfa.accept(""));
+    assert!(!nfa.accept("bb"));
+    assert!(!nfa.accept("c"));
+
+    let nfa = from_regex("(+a)b");
+    assert!(nfa.accept("b"));
+    assert!(nfa.accept("ab"));
+    assert!(!nfa.accept("aab"));
+}
+
+#[test]
+fn regex_round_trip_test() {
+    let mut q0: NfaState<String> = NfaState::new(0, false);
+    q0.add_transition(String::from("a"), 0);
+    q0.add_transition(String::from("b"), 0);
+    q0.add_transition(String::from("a"), 1);
+
+    let mut q1: NfaState<String> = NfaState::new(1, true);
+    q1.add_transition(String::from("b"), 1);
+    q1.add_transition(String::from("b"), 0);
+
+    let mut q2: NfaState<String> = NfaState::new(2, true);
+    q2.add_transition(String::from("ab"), 0);
+
+    let mut nfa: Nfa<String> = Nfa::new(2);
+    nfa.add_state(q0);
+    nfa.add_state(q1);
+    nfa.add_state(q2);
+
+    let parsed = from_regex(&nfa.to_regex());
+    for word in words(&['a', 'b'], 7) {
+        assert_eq!(parsed.accept(&word), nfa.accept(&word), "{word}");
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+use automaton::automaton::Automaton;
+use automaton::dfa::{ToDfa, ToMinimalCompleteDfa};
+use automaton::nfa::{Nfa, NfaState};
+
+use crate::cli::{parse_automaton, regex_of, write_nfa, Format};
+
+const HELP: &str = "\
+Commands:
+    new <name> [start]              empty automaton with a single start state
+    state <name> <index> [terminal] add a state
+    terminal <name> <index>...      mark states as terminal
+    trans <name> <from> <word> <to> add a transition, \"\" is the empty word
+    start <name> <index>            set the start state
+    to_dfa <name> [result]          determinize
+    to_mcdfa <name> [result]        minimal complete DFA
+    complement <name> [result]      complement over the automaton's alphabet
+    to_regex <name>                 equivalent regular expression
+    accept <name> <word>...         test words
+    show <name>                     print an automaton
+    list                            names in this session
+    delete <name>                   forget an automaton
+    save <name> <file>              write in the format given by the file
+    load <name> <file>              extension: .txt, .dot or .json
+    help                            print this message
+    quit                            leave the session
+
+Words may be quoted as \"a b\" with \\\", \\\\ and \\n escapes. Results are
+stored under the result name when one is given.";
+
+/// Splits a command line into words. Double quotes group words that contain
+/// spaces and allow the empty word.
+fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut tokens = vec![];
+    let mut chars = line.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut token = String::new();
+        if ch == '"' {
+            chars.next();
+            loop {
+                match chars.next() {
+                    None => return Err(String::from("unterminated quote")),
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => token.push('\n'),
+                        Some('t') => token.push('\t'),
+                        Some(ch @ ('"' | '\\')) => token.push(ch),
+                        _ => return Err(String::from("unknown escape sequence")),
+                    },
+                    Some(ch) => token.push(ch),
+                }
+            }
+        } else {
+            while let Some(&ch) = chars.peek() {
+                if ch.is_whitespace() {
+                    break;
+                }
+                token.push(ch);
+                chars.next();
+            }
+        }
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn index(word: &str) -> Result<usize, String> {
+    word.parse().map_err(|_| format!("'{word}' is not a state index"))
+}
+
+/// Named automata of one interactive session.
+#[derive(Default)]
+pub struct Session {
+    automata: BTreeMap<String, Nfa<String>>
+}
+
+impl Session {
+    fn get(&self, name: &str) -> Result<&Nfa<String>, String> {
+        self.automata.get(name).ok_or(format!("no automaton named '{name}'"))
+    }
+
+    fn get_mut(&mut self, name: &str) -> Result<&mut Nfa<String>, String> {
+        self.automata.get_mut(name).ok_or(format!("no automaton named '{name}'"))
+    }
+
+    fn ensure_state(nfa: &mut Nfa<String>, idx: usize) {
+        if !nfa.states.contains_key(&idx) {
+            nfa.add_state(NfaState::new(idx, false));
+        }
+    }
+
+    /// Stores `nfa` under `result` when given and returns its text form.
+    fn store(&mut self, nfa: Nfa<String>, result: Option<&String>) -> String {
+        let text = nfa.to_string();
+        if let Some(name) = result {
+            self.automata.insert(name.clone(), nfa);
+        }
+        text
+    }
+
+    /// Runs one command and returns what it prints.
+    pub fn execute(&mut self, args: &[String]) -> Result<String, String> {
+        let Some((command, args)) = args.split_first() else {
+            return Ok(String::new());
+        };
+        let arity = |min: usize, max: usize| {
+            if args.len() < min || args.len() > max {
+                return Err(format!("wrong number of arguments for '{command}', see 'help'"));
+            }
+            Ok(())
+        };
+        match command.as_str() {
+            "new" => {
+                arity(1, 2)?;
+                let start = args.get(1).map_or(Ok(0), |word| index(word))?;
+                let mut nfa: Nfa<String> = Nfa::new(start);
+                nfa.add_state(NfaState::new(start, false));
+                self.automata.insert(args[0].clone(), nfa);
+                Ok(String::new())
+            }
+            "state" => {
+                arity(2, 3)?;
+                let idx = index(&args[1])?;
+                let is_terminal = match args.get(2).map(String::as_str) {
+                    None => false,
+                    Some("terminal") => true,
+                    Some(word) => return Err(format!("expected 'terminal', found '{word}'")),
+                };
+                let nfa = self.get_mut(&args[0])?;
+                nfa.try_add_state(NfaState::new(idx, is_terminal)).map_err(|error| error.to_string())?;
+                Ok(String::new())
+            }
+            "terminal" => {
+                arity(2, usize::MAX)?;
+                let indices = args[1..].iter().map(|word| index(word)).collect::<Result<Vec<_>, _>>()?;
+                let nfa = self.get_mut(&args[0])?;
+                for idx in indices {
+                    Self::ensure_state(nfa, idx);
+                    nfa.states.get_mut(&idx).unwrap().is_terminal = true;
+                }
+                Ok(String::new())
+            }
+            "trans" => {
+                arity(4, 4)?;
+                let (from, to) = (index(&args[1])?, index(&args[3])?);
+                let nfa = self.get_mut(&args[0])?;
+                Self::ensure_state(nfa, from);
+                Self::ensure_state(nfa, to);
+                nfa.states.get_mut(&from).unwrap().add_transition(args[2].clone(), to);
+                Ok(String::new())
+            }
+            "start" => {
+                arity(2, 2)?;
+                let idx = index(&args[1])?;
+                let nfa = self.get_mut(&args[0])?;
+                Self::ensure_state(nfa, idx);
+                nfa.starting_state = idx;
+                Ok(String::new())
+            }
+            "to_dfa" | "to_mcdfa" | "complement" => {
+                arity(1, 2)?;
+                let nfa = self.get(&args[0])?;
+                let dfa = match command.as_str() {
+                    "to_dfa" => nfa.to_dfa(),
+                    "to_mcdfa" => nfa.to_mcdfa(),
+                    _ => nfa.to_dfa().complement(),
+                };
+                Ok(self.store(dfa.to_nfa(), args.get(1)))
+            }
+            "to_regex" => {
+                arity(1, 1)?;
+                Ok(format!("{}\n", regex_of(self.get(&args[0])?)?))
+            }
+            "accept" => {
+                arity(2, usize::MAX)?;
+                let nfa = self.get(&args[0])?;
+                let mut output = String::new();
+                for word in &args[1..] {
+                    let verdict = if nfa.accept(word) { "accept" } else { "reject" };
+                    output.push_str(&format!("{verdict}\t{word:?}\n"));
+                }
+                Ok(output)
+            }
+            "show" => {
+                arity(1, 1)?;
+                Ok(self.get(&args[0])?.to_string())
+            }
+            "list" => {
+                arity(0, 0)?;
+                Ok(self.automata.keys().map(|name| format!("{name}\n")).collect())
+            }
+            "delete" => {
+                arity(1, 1)?;
+                self.automata.remove(&args[0]).ok_or(format!("no automaton named '{}'", args[0]))?;
+                Ok(String::new())
+            }
+            "save" => {
+                arity(2, 2)?;
+                let text = write_nfa(self.get(&args[0])?, Format::from_path(&args[1]));
+                fs::write(&args[1], text).map_err(|error| format!("{}: {error}", args[1]))?;
+                Ok(String::new())
+            }
+            "load" => {
+                arity(2, 2)?;
+                let src = fs::read_to_string(&args[1]).map_err(|error| format!("{}: {error}", args[1]))?;
+                let nfa = parse_automaton(&src, Format::from_path(&args[1]))
+                    .map_err(|error| format!("{}:{error}", args[1]))?;
+                self.automata.insert(args[0].clone(), nfa);
+                Ok(String::new())
+            }
+            "help" => Ok(format!("{HELP}\n")),
+            _ => Err(format!("unknown command '{command}', see 'help'")),
+        }
+    }
+}
+
+/// Reads commands line by line until `quit` or the end of the input. Errors
+/// are reported and do not end the session.
+pub fn run<R: BufRead, W: Write>(input: &mut R, output: &mut W) -> io::Result<()> {
+    let mut session = Session::default();
+    let mut line = String::new();
+    loop {
+        write!(output, "> ")?;
+        output.flush()?;
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            writeln!(output)?;
+            return Ok(());
+        }
+        let result = tokenize(&line).and_then(|args| {
+            if matches!(args.first().map(String::as_str), Some("quit" | "exit")) {
+                return Ok(None);
+            }
+            session.execute(&args).map(Some)
+        });
+        match result {
+            Ok(None) => return Ok(()),
+            Ok(Some(text)) => write!(output, "{text}")?,
+            Err(error) => writeln!(output, "error: {error}")?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,85 @@
+use std::env;
+use std::io::Cursor;
+
+use crate::repl::*;
+
+fn session(input: &str) -> String {
+    let mut output = vec![];
+    run(&mut Cursor::new(input), &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn build_and_query_test() {
+    let output = session("\
+new ab
+trans ab 0 a 0
+trans ab 0 b 0
+trans ab 0 \"ab\" 1
+terminal ab 1
+accept ab bab ba \"\"
+to_mcdfa ab min
+accept min aab
+list
+quit
+accept ab ab
+");
+    assert_eq!(output, "\
+> > > > > > accept\t\"bab\"
+reject\t\"ba\"
+reject\t\"\"
+> Starting state: 0
+0 -> 1 by \"a\"
+0 -> 0 by \"b\"
+1 -> 1 by \"a\"
+1 -> 2 by \"b\"
+2 -> 1 by \"a\"
+2 -> 0 by \"b\"
+Terminal states:
+2
+> accept\t\"aab\"
+> ab
+min
+> ");
+}
+
+#[test]
+fn states_and_errors_test() {
+    let output = session("\
+new e 5
+state e 5
+state e 7 terminal
+trans e 5 \"\" 7
+start e 7
+accept e \"\"
+complement e c
+to_regex c
+show missing
+frobnicate
+trans e 5 \"a
+delete e
+delete e
+");
+    assert!(output.contains("error: duplicate state 5\n"));
+    assert!(output.contains("accept\t\"\"\n"));
+    assert!(output.contains("error: the language is empty or has a symbol '+', '*', '(' or ')' and has no regular expression\n"));
+    assert!(output.contains("error: no automaton named 'missing'\n"));
+    assert!(output.contains("error: unknown command 'frobnicate', see 'help'\n"));
+    assert!(output.contains("error: unterminated quote\n"));
+    assert!(output.ends_with("error: no automaton named 'e'\n> \n"));
+}
+
+#[test]
+fn save_and_load_test() {
+    let dir = env::temp_dir().join(format!("automaton-repl-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut input = String::from("new a\ntrans a 0 \"x y\" 1\nterminal a 1\n");
+    for extension in ["txt", "dot", "json"] {
+        let path = dir.join(format!("a.{extension}"));
+        input.push_str(&format!("save a \"{}\"\nload b \"{}\"\naccept b \"x y\" x\n", path.display(), path.display()));
+    }
+    let output = session(&input);
+    assert_eq!(output.matches("accept\t\"x y\"\nreject\t\"x\"\n").count(), 3);
+    assert!(!output.contains("error"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
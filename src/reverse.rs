@@ -0,0 +1,115 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Display;
+
+use crate::dfa::{Dfa, DfaState, ToDfa};
+use crate::nfa::{Nfa, NfaState};
+use crate::state::Traversable;
+
+pub trait Reverse {
+    /// Automaton of the reversed words: every transition is turned around
+    /// and its word reversed, the former starting state becomes the only
+    /// terminal state, and a fresh starting state has empty-word transitions
+    /// to the former terminal states.
+    fn reverse(&self) -> Nfa<String>;
+
+    /// Brzozowski's minimization: reverse, determinize, reverse, determinize.
+    /// The result is completed over every symbol of the automaton, including
+    /// those that only lead to dead states and so vanish in the reversal,
+    /// so it matches `to_mcdfa` up to the numbering of states.
+    fn to_mcdfa_brzozowski(&self) -> Dfa {
+        let reversed = self.reverse();
+        let alphabet: BTreeSet<char> = reversed.states.values()
+            .flat_map(|state| state.transitions.iter())
+            .flat_map(|(word, _)| word.chars())
+            .collect();
+        determinize_reverse(&reversed.to_dfa()).to_cdfa_over(&alphabet)
+    }
+}
+
+/// Subset construction on the reverse of `dfa`, starting from the set of its
+/// terminal states rather than from a fresh state with empty-word
+/// transitions, which would add a redundant copy of that set. When every
+/// state of `dfa` is reachable the result is minimal.
+fn determinize_reverse(dfa: &Dfa) -> Dfa {
+    let mut predecessors: BTreeMap<(usize, char), Vec<usize>> = BTreeMap::new();
+    for state in dfa.states.values() {
+        for (ch, next_state) in state.transitions().iter() {
+            predecessors.entry((*next_state, *ch)).or_default().push(state.index);
+        }
+    }
+    let alphabet = dfa.get_alphabet();
+
+    let initial: BTreeSet<usize> = dfa.states.values().filter(|state| state.is_terminal).map(|state| state.index).collect();
+    let mut index: BTreeMap<BTreeSet<usize>, usize> = BTreeMap::from([(initial.clone(), 0)]);
+    let mut queue: VecDeque<BTreeSet<usize>> = VecDeque::from([initial]);
+    let mut result = Dfa::new(0);
+    while let Some(subset) = queue.pop_front() {
+        let mut state = DfaState::new(index[&subset], subset.contains(&dfa.starting_state));
+        for ch in alphabet.iter() {
+            let next: BTreeSet<usize> = subset.iter()
+                .filter_map(|idx| predecessors.get(&(*idx, *ch)))
+                .flatten()
+                .copied()
+                .collect();
+            if next.is_empty() {
+                continue;
+            }
+            let len = index.len();
+            let next_idx = *index.entry(next.clone()).or_insert_with(|| {
+                queue.push_back(next);
+                len
+            });
+            state.add_transition(*ch, next_idx);
+        }
+        result.add_state(state);
+    }
+    result
+}
+
+fn reverse_edges(starting_state: usize, states: Vec<(usize, bool)>, edges: Vec<(usize, String, usize)>) -> Nfa<String> {
+    let start = states.iter().map(|(idx, _)| idx + 1).max().unwrap_or(0);
+    let mut result: Nfa<String> = Nfa::new(start);
+    let mut new_start = NfaState::new(start, false);
+    for (idx, is_terminal) in states.iter() {
+        if *is_terminal {
+            new_start.add_transition(String::new(), *idx);
+        }
+        result.add_state(NfaState::new(*idx, *idx == starting_state));
+    }
+    result.add_state(new_start);
+    for (from, word, to) in edges {
+        if let Some(state) = result.states.get_mut(&to) {
+            state.add_transition(word.chars().rev().collect(), from);
+        }
+    }
+    result
+}
+
+impl<T: Traversable + Display + Clone> Reverse for Nfa<T> {
+    fn reverse(&self) -> Nfa<String> {
+        let states = self.states.values().map(|state| (state.index, state.is_terminal)).collect();
+        let mut edges = vec![];
+        for state in self.states.values() {
+            for (word, next_state) in state.transitions.iter() {
+                edges.push((state.index, word.to_string(), *next_state));
+            }
+        }
+        reverse_edges(self.starting_state, states, edges)
+    }
+}
+
+impl Reverse for Dfa {
+    fn reverse(&self) -> Nfa<String> {
+        let states = self.states.values().map(|state| (state.index, state.is_terminal)).collect();
+        let mut edges = vec![];
+        for state in self.states.values() {
+            for (ch, next_state) in state.transitions().iter() {
+                edges.push((state.index, ch.to_string(), *next_state));
+            }
+        }
+        reverse_edges(self.starting_state, states, edges)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,80 @@
+use std::collections::BTreeSet;
+
+use crate::automaton::*;
+use crate::dfa::*;
+use crate::language::*;
+use crate::nfa::*;
+use crate::reverse::*;
+use crate::words::*;
+use crate::testing::*;
+
+#[test]
+fn reverse_nfa_test() {
+    let mut q0: NfaState<String> = NfaState::new(0, false);
+    q0.add_transition(String::from("ab"), 1);
+    q0.add_transition(String::from("日本"), 0);
+    let mut q1: NfaState<String> = NfaState::new(1, true);
+    q1.add_transition(String::from(""), 0);
+    let mut nfa: Nfa<String> = Nfa::new(0);
+    nfa.add_state(q0);
+    nfa.add_state(q1);
+
+    let reversed = nfa.reverse();
+    assert_eq!(reversed.to_string(), "\
+Starting state: 2
+0 -> 0 by \"本日\"
+0 -> 1 by \"\"
+1 -> 0 by \"ba\"
+2 -> 1 by \"\"
+Terminal states:
+0
+");
+    assert!(reversed.accept("ba本日"));
+    assert!(!reversed.accept("ab"));
+    assert!(matches!(reversed.reverse().check_equivalence(&nfa), Equivalence::Equivalent));
+}
+
+#[test]
+fn reverse_words_test() {
+    let nfa = from_regex("a(ab+b)*c+bb");
+    let reversed = nfa.split_words().reverse();
+    let mut expected: Vec<String> = nfa.words().max_len(6).map(|word| word.chars().rev().collect()).collect();
+    let mut actual: Vec<String> = reversed.words().max_len(6).collect();
+    expected.sort();
+    actual.sort();
+    assert_eq!(actual, expected);
+
+    let dfa = nfa.to_dfa();
+    assert!(dfa.reverse().accept("cbbaa"));
+    assert!(matches!(dfa.reverse().check_equivalence(&reversed), Equivalence::Equivalent));
+}
+
+#[test]
+fn brzozowski_test() {
+    for pattern in ["(a+b)*abb", "((a+b)(a+b))*", "a*b*+b*a*", "", "(ab+ba)*(a+)"] {
+        let nfa = from_regex(pattern);
+        let minimal = nfa.to_mcdfa_brzozowski();
+        let hopcroft = nfa.to_mcdfa();
+        assert_eq!(minimal.states.len(), hopcroft.states.len(), "{pattern}");
+        assert_eq!(minimal.get_alphabet(), hopcroft.get_alphabet(), "{pattern}");
+        assert!(matches!(minimal.check_equivalence(&hopcroft), Equivalence::Equivalent), "{pattern}");
+    }
+
+    // {a} over {a, b}: "b" only leads to a dead state.
+    let mut q0: NfaState<char> = NfaState::new(0, false);
+    q0.add_transition('a', 1);
+    q0.add_transition('b', 2);
+    let mut nfa: Nfa<char> = Nfa::new(0);
+    nfa.add_state(q0);
+    nfa.add_state(NfaState::new(1, true));
+    nfa.add_state(NfaState::new(2, false));
+    let minimal = nfa.to_mcdfa_brzozowski();
+    let hopcroft = nfa.to_mcdfa();
+    assert_eq!(minimal.get_alphabet(), BTreeSet::from(['a', 'b']));
+    assert_eq!(minimal.get_alphabet(), hopcroft.get_alphabet());
+    assert_eq!(minimal.states.len(), hopcroft.states.len());
+
+    let empty: Nfa<char> = Nfa::new(0);
+    assert_eq!(empty.reverse().states.len(), 1);
+    assert!(matches!(empty.to_mcdfa_brzozowski().is_empty(), Emptiness::Empty));
+}
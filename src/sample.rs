@@ -0,0 +1,111 @@
+use crate::bigint::BigUint;
+use crate::count::suffix_counts;
+use crate::dfa::{Dfa, Trimmed};
+
+/// Source of uniformly distributed 64-bit values, so any generator can be
+/// plugged into the sampler.
+pub trait RandomSource {
+    fn next_u64(&mut self) -> u64;
+}
+
+/// Small seedable generator (SplitMix64). Good enough for test input
+/// generation, not for cryptography.
+#[derive(Clone, Debug)]
+pub struct SplitMix64 {
+    state: u64
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self{state: seed}
+    }
+}
+
+impl RandomSource for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Uniform value in `0..bound` by rejection sampling, which needs less than
+/// two draws of `bound.bits()` bits on average.
+fn random_below<R: RandomSource>(bound: &BigUint, rng: &mut R) -> BigUint {
+    let bits = bound.bits();
+    loop {
+        let mut limbs = vec![];
+        while limbs.len() * 32 < bits {
+            let value = rng.next_u64();
+            limbs.push(value as u32);
+            limbs.push((value >> 32) as u32);
+        }
+        limbs.truncate(bits.div_ceil(32));
+        if !bits.is_multiple_of(32) {
+            *limbs.last_mut().unwrap() &= (1 << (bits % 32)) - 1;
+        }
+        let value = BigUint::from_limbs(limbs);
+        if value < *bound {
+            return value;
+        }
+    }
+}
+
+/// Draws accepted words of one fixed length uniformly at random. Each symbol
+/// is chosen with probability proportional to the number of accepted
+/// suffixes it leads to.
+pub struct Sampler {
+    trimmed: Trimmed,
+    counts: Vec<Vec<BigUint>>,
+    len: usize
+}
+
+impl Sampler {
+    /// Number of words the sampler chooses from.
+    pub fn count(&self) -> BigUint {
+        match self.trimmed.starting_state {
+            Some(start) => self.counts[self.len][start].clone(),
+            None => BigUint::zero(),
+        }
+    }
+
+    /// `None` when no word of the requested length is accepted.
+    pub fn sample<R: RandomSource>(&self, rng: &mut R) -> Option<String> {
+        let mut cur = self.trimmed.starting_state?;
+        if self.counts[self.len][cur].is_zero() {
+            return None;
+        }
+        let mut word = String::new();
+        for r in (1..=self.len).rev() {
+            let mut choice = random_below(&self.counts[r][cur], rng);
+            for (ch, next_state) in self.trimmed.transitions[cur].iter() {
+                let count = &self.counts[r - 1][*next_state];
+                if choice < *count {
+                    word.push(*ch);
+                    cur = *next_state;
+                    break;
+                }
+                choice = &choice - count;
+            }
+        }
+        Some(word)
+    }
+}
+
+impl Dfa {
+    pub fn sampler(&self, len: usize) -> Sampler {
+        let trimmed = self.trimmed();
+        let counts = suffix_counts(&trimmed, len);
+        Sampler{trimmed, counts, len}
+    }
+
+    /// Uniformly random accepted word of exactly `len` symbols.
+    pub fn sample<R: RandomSource>(&self, len: usize, rng: &mut R) -> Option<String> {
+        self.sampler(len).sample(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use crate::automaton::*;
+use crate::bigint::*;
+use crate::sample::*;
+use crate::testing::*;
+
+#[test]
+fn uniform_sample_test() {
+    // A random walk would pick "bbb" half of the time.
+    let dfa = dfa_from_regex("a(a+b)(a+b)+bbb");
+    let sampler = dfa.sampler(3);
+    let mut rng = SplitMix64::new(7);
+    let mut histogram: BTreeMap<String, usize> = BTreeMap::new();
+    for _ in 0..5000 {
+        let word = sampler.sample(&mut rng).unwrap();
+        assert!(dfa.accept(&word));
+        *histogram.entry(word).or_default() += 1;
+    }
+
+    assert_eq!(sampler.count(), BigUint::from(5));
+    assert_eq!(histogram.len(), 5);
+    for count in histogram.values() {
+        assert!((850..1150).contains(count), "{histogram:?}");
+    }
+}
+
+#[test]
+fn seeded_sample_test() {
+    let dfa = dfa_from_regex("(a+b+c)*c(a+b)*");
+    let mut rng = SplitMix64::new(42);
+    let first: Vec<Option<String>> = (0..5).map(|_| dfa.sample(40, &mut rng)).collect();
+    let mut rng = SplitMix64::new(42);
+    for word in first {
+        let word = word.unwrap();
+        assert_eq!(Some(word.clone()), dfa.sample(40, &mut rng));
+        assert_eq!(word.chars().count(), 40);
+        assert!(dfa.accept(&word));
+    }
+}
+
+#[test]
+fn no_word_of_length_test() {
+    let mut rng = SplitMix64::new(1);
+    assert_eq!(dfa_from_regex("(aa)*").sample(3, &mut rng), None);
+    assert_eq!(dfa_from_regex("(aa)*").sample(0, &mut rng), Some(String::new()));
+    assert_eq!(dfa_from_regex("(aa)*").sampler(3).count(), BigUint::zero());
+}
+
+#[test]
+fn large_count_sample_test() {
+    let dfa = dfa_from_regex("(a+b)*");
+    let mut rng = SplitMix64::new(3);
+    let word = dfa.sample(200, &mut rng).unwrap();
+
+    assert_eq!(word.len(), 200);
+    assert!(word.contains('a') && word.contains('b'));
+}
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::Range;
+
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+use crate::state::Traversable;
+
+/// Which match to report when several matches start at the leftmost
+/// position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MatchKind {
+    /// The match of the highest priority run, where transitions leaving a
+    /// state are preferred in the order they were added and stopping at a
+    /// terminal state comes after all of them. For an automaton built from a
+    /// regex this is the usual backtracking result: alternatives are tried
+    /// left to right and stars are greedy.
+    #[default]
+    LeftmostFirst,
+    /// The longest match.
+    LeftmostLongest,
+}
+
+#[derive(Clone, Copy)]
+enum Edge {
+    Epsilon(usize),
+    Symbol(char, usize),
+}
+
+/// Ordered choice of a run at the current position: follow a symbol
+/// transition or stop with a match. The second field is where the run
+/// started.
+#[derive(Clone, Copy)]
+enum Leaf {
+    Symbol(char, usize, usize),
+    Match(usize),
+}
+
+/// Index, terminal flag and labelled transitions of one state.
+type StateEdges = (usize, bool, Vec<(String, usize)>);
+
+/// Compiled form of an automaton for unanchored search. Multi-symbol labels
+/// are split into chains of fresh states so every edge reads at most one
+/// symbol; the order of edges is kept, as it decides `LeftmostFirst`
+/// matches.
+#[derive(Clone)]
+pub struct Searcher {
+    kind: MatchKind,
+    starting_state: Option<usize>,
+    is_terminal: Vec<bool>,
+    edges: Vec<Vec<Edge>>
+}
+
+impl Searcher {
+    fn new(kind: MatchKind, starting_state: usize, states: Vec<StateEdges>) -> Self {
+        let dense: HashMap<usize, usize> =
+            states.iter().enumerate().map(|(i, (idx, _, _))| (*idx, i)).collect();
+        let mut is_terminal: Vec<bool> = states.iter().map(|(_, is_terminal, _)| *is_terminal).collect();
+        let mut edges: Vec<Vec<Edge>> = vec![vec![]; states.len()];
+        for (i, (_, _, transitions)) in states.iter().enumerate() {
+            for (word, next_state) in transitions {
+                let Some(&target) = dense.get(next_state) else {
+                    continue;
+                };
+                let chars: Vec<char> = word.chars().collect();
+                if chars.is_empty() {
+                    edges[i].push(Edge::Epsilon(target));
+                    continue;
+                }
+                let mut cur = i;
+                for (j, ch) in chars.iter().enumerate() {
+                    let next = if j == chars.len() - 1 {
+                        target
+                    } else {
+                        edges.push(vec![]);
+                        is_terminal.push(false);
+                        edges.len() - 1
+                    };
+                    edges[cur].push(Edge::Symbol(*ch, next));
+                    cur = next;
+                }
+            }
+        }
+        Self{kind, starting_state: dense.get(&starting_state).copied(), is_terminal, edges}
+    }
+
+    pub fn kind(&self) -> MatchKind {
+        self.kind
+    }
+
+    /// Appends the choices of the runs in `entered` after following
+    /// empty-word transitions, in priority order. A state reached twice keeps
+    /// only its first, higher priority run.
+    fn closure(&self, entered: &[(usize, usize)], seen: &mut [usize], stamp: usize, leaves: &mut Vec<Leaf>) {
+        let mut stack = vec![];
+        for &(state, start) in entered {
+            if seen[state] == stamp {
+                continue;
+            }
+            seen[state] = stamp;
+            stack.push((state, 0));
+            while let Some((cur, i)) = stack.pop() {
+                let Some(edge) = self.edges[cur].get(i) else {
+                    if self.is_terminal[cur] {
+                        leaves.push(Leaf::Match(start));
+                    }
+                    continue;
+                };
+                stack.push((cur, i + 1));
+                match *edge {
+                    Edge::Epsilon(next) => {
+                        if seen[next] != stamp {
+                            seen[next] = stamp;
+                            stack.push((next, 0));
+                        }
+                    }
+                    Edge::Symbol(ch, next) => leaves.push(Leaf::Symbol(ch, next, start)),
+                }
+            }
+        }
+    }
+
+    /// Runs started at every position from `from` on are simulated side by
+    /// side, earlier starts with higher priority, until the leftmost match is
+    /// settled. With `earliest` the first match seen is returned.
+    fn search(&self, haystack: &str, from: usize, earliest: bool) -> Option<Range<usize>> {
+        let start = self.starting_state?;
+        let mut seen = vec![usize::MAX; self.edges.len()];
+        let mut threads: Vec<(usize, usize)> = vec![];
+        let mut leaves = vec![];
+        let mut best: Option<Range<usize>> = None;
+        let mut pos = from;
+        loop {
+            if best.is_none() {
+                threads.push((start, pos));
+            }
+            if threads.is_empty() {
+                return best;
+            }
+            leaves.clear();
+            self.closure(&threads, &mut seen, pos, &mut leaves);
+            threads.clear();
+            let ch = haystack[pos..].chars().next();
+            for leaf in leaves.iter() {
+                match *leaf {
+                    Leaf::Match(run_start) => {
+                        // Later matches come from runs that started no later
+                        // and, for `LeftmostFirst`, have a higher priority.
+                        let better = match (&best, self.kind) {
+                            (None, _) | (_, MatchKind::LeftmostFirst) => true,
+                            (Some(range), MatchKind::LeftmostLongest) => run_start < range.start || pos > range.end,
+                        };
+                        if better {
+                            best = Some(run_start..pos);
+                        }
+                        if earliest {
+                            return best;
+                        }
+                        if self.kind == MatchKind::LeftmostFirst {
+                            break;
+                        }
+                    }
+                    Leaf::Symbol(symbol, next, run_start) => {
+                        if Some(symbol) == ch {
+                            threads.push((next, run_start));
+                        }
+                    }
+                }
+            }
+            if let Some(range) = &best {
+                threads.retain(|(_, run_start)| *run_start <= range.start);
+            }
+            match ch {
+                Some(ch) => pos += ch.len_utf8(),
+                None => return best,
+            }
+        }
+    }
+
+    pub fn is_match(&self, haystack: &str) -> bool {
+        self.search(haystack, 0, true).is_some()
+    }
+
+    /// Byte range of the leftmost match.
+    pub fn find(&self, haystack: &str) -> Option<Range<usize>> {
+        self.find_at(haystack, 0)
+    }
+
+    /// Like `find`, but only matches starting at byte `start` or later are
+    /// reported. Panics when `start` is not a char boundary.
+    pub fn find_at(&self, haystack: &str, start: usize) -> Option<Range<usize>> {
+        self.search(haystack, start, false)
+    }
+
+    /// Successive non-overlapping matches. An empty match that ends where the
+    /// previous match ended is skipped.
+    pub fn find_iter<'h>(&self, haystack: &'h str) -> FindIter<'h> {
+        FindIter{searcher: self.clone(), haystack, pos: 0, last_end: None}
+    }
+}
+
+pub struct FindIter<'h> {
+    searcher: Searcher,
+    haystack: &'h str,
+    pos: usize,
+    last_end: Option<usize>
+}
+
+impl Iterator for FindIter<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        loop {
+            if self.pos > self.haystack.len() {
+                return None;
+            }
+            let range = self.searcher.find_at(self.haystack, self.pos)?;
+            if range.is_empty() && Some(range.end) == self.last_end {
+                let ch = self.haystack[range.end..].chars().next()?;
+                self.pos = range.end + ch.len_utf8();
+                continue;
+            }
+            self.pos = range.end;
+            self.last_end = Some(range.end);
+            return Some(range);
+        }
+    }
+}
+
+/// Unanchored search: a match is any substring of the haystack that the
+/// automaton accepts. The provided methods use `MatchKind::LeftmostFirst`
+/// and compile the automaton on every call; keep a `Searcher` to scan many
+/// haystacks.
+pub trait Search {
+    fn searcher(&self, kind: MatchKind) -> Searcher;
+
+    fn is_match(&self, haystack: &str) -> bool {
+        self.searcher(MatchKind::LeftmostFirst).is_match(haystack)
+    }
+
+    fn find(&self, haystack: &str) -> Option<Range<usize>> {
+        self.searcher(MatchKind::LeftmostFirst).find(haystack)
+    }
+
+    fn find_iter<'h>(&self, haystack: &'h str) -> FindIter<'h> {
+        self.searcher(MatchKind::LeftmostFirst).find_iter(haystack)
+    }
+}
+
+impl<T: Traversable + Display + Clone> Search for Nfa<T> {
+    fn searcher(&self, kind: MatchKind) -> Searcher {
+        let states = self.states.values()
+            .map(|state| {
+                let transitions = state.transitions.iter().map(|(word, next_state)| (word.to_string(), *next_state)).collect();
+                (state.index, state.is_terminal, transitions)
+            })
+            .collect();
+        Searcher::new(kind, self.starting_state, states)
+    }
+}
+
+impl Search for Dfa {
+    fn searcher(&self, kind: MatchKind) -> Searcher {
+        let states = self.states.values()
+            .map(|state| {
+                let transitions = state.transitions().iter().map(|(ch, next_state)| (ch.to_string(), *next_state)).collect();
+                (state.index, state.is_terminal, transitions)
+            })
+            .collect();
+        Searcher::new(kind, self.starting_state, states)
+    }
+}
+
+#[cfg(test)]
+mod tests;
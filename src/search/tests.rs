@@ -0,0 +1,78 @@
+use std::ops::Range;
+
+use crate::dfa::*;
+use crate::nfa::*;
+use crate::search::*;
+use crate::testing::*;
+
+fn all(searcher: &Searcher, haystack: &str) -> Vec<Range<usize>> {
+    searcher.find_iter(haystack).collect()
+}
+
+#[test]
+fn find_test() {
+    let nfa = from_regex("ab+b");
+    assert!(nfa.is_match("xxabyy"));
+    assert!(!nfa.is_match("xxayy"));
+    assert_eq!(nfa.find("xxabyy"), Some(2..4));
+    assert_eq!(nfa.find("xxbab"), Some(2..3));
+    assert_eq!(nfa.find(""), None);
+    assert_eq!(nfa.find_iter("ab b abb").collect::<Vec<_>>(), vec![0..2, 3..4, 5..7, 7..8]);
+
+    let dfa = from_regex("日本").to_mcdfa();
+    assert_eq!(dfa.find("こんにちは日本"), Some(15..21));
+    assert_eq!(dfa.find_iter("日本日本").count(), 2);
+}
+
+#[test]
+fn match_kind_test() {
+    let nfa = from_regex("a+ab");
+    let first = nfa.searcher(MatchKind::LeftmostFirst);
+    let longest = nfa.searcher(MatchKind::LeftmostLongest);
+    assert_eq!(first.kind(), MatchKind::LeftmostFirst);
+    assert_eq!(first.find("xab"), Some(1..2));
+    assert_eq!(longest.find("xab"), Some(1..3));
+
+    // Stars are greedy under leftmost-first.
+    let nfa = from_regex("a*");
+    assert_eq!(nfa.searcher(MatchKind::LeftmostFirst).find("aaab"), Some(0..3));
+
+    // A terminal state prefers to continue, like a greedy star.
+    let dfa = Dfa::builder().start(0).state(0, false).state(1, true)
+        .transition(0, 'a', 1).transition(1, 'b', 0).build().unwrap();
+    assert_eq!(dfa.find("zababx"), Some(1..4));
+    assert_eq!(dfa.searcher(MatchKind::LeftmostLongest).find("zabab"), Some(1..4));
+    assert_eq!(dfa.find("abac"), Some(0..3));
+    assert_eq!(dfa.find("abc"), Some(0..1));
+
+    // Priority follows the order of alternatives, not the length.
+    let nfa = from_regex("ab+abcd+abc");
+    assert_eq!(nfa.searcher(MatchKind::LeftmostFirst).find("xabcd"), Some(1..3));
+    assert_eq!(nfa.searcher(MatchKind::LeftmostLongest).find("xabcd"), Some(1..5));
+}
+
+#[test]
+fn leftmost_test() {
+    // The match starting first wins even when a later one ends first.
+    let nfa = from_regex("abcd+bc");
+    let longest = nfa.searcher(MatchKind::LeftmostLongest);
+    assert_eq!(nfa.find("abcd"), Some(0..4));
+    assert_eq!(longest.find("abcd"), Some(0..4));
+    assert_eq!(nfa.find("abce"), Some(1..3));
+    assert_eq!(longest.find_at("abcd", 1), Some(1..3));
+    assert_eq!(all(&longest, "abcdbcabc"), vec![0..4, 4..6, 7..9]);
+}
+
+#[test]
+fn empty_match_test() {
+    let nfa = from_regex("a*");
+    let longest = nfa.searcher(MatchKind::LeftmostLongest);
+    assert_eq!(all(&longest, "baaéa"), vec![0..0, 1..3, 5..6]);
+
+    let mut q0: NfaState<String> = NfaState::new(0, true);
+    q0.add_transition(String::from("xyz"), 0);
+    let mut nfa: Nfa<String> = Nfa::new(0);
+    nfa.add_state(q0);
+    assert_eq!(nfa.find_iter("xyzxy").collect::<Vec<_>>(), vec![0..3, 4..4, 5..5]);
+    assert!(!Nfa::<char>::new(0).is_match("a"));
+}
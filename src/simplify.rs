@@ -0,0 +1,219 @@
+use crate::automaton::ToRegex;
+use crate::dfa::ToDfa;
+use crate::regex::Regex;
+
+/// Symbols that `to_regex` writes as operators.
+const OPERATORS: [char; 4] = ['+', '*', '(', ')'];
+
+pub trait ToSimplifiedRegex {
+    /// `to_regex` output after `Regex::simplify`, or `None` when the
+    /// language is empty or a symbol of the alphabet is one of `+`, `*`,
+    /// `(` and `)`: the syntax has no escapes, so such a symbol would be
+    /// read back as an operator.
+    fn to_simplified_regex(&self) -> Option<String>;
+}
+
+impl<T: ToDfa> ToSimplifiedRegex for T {
+    fn to_simplified_regex(&self) -> Option<String> {
+        let dfa = self.to_dfa();
+        if dfa.get_alphabet().iter().any(|ch| OPERATORS.contains(ch)) {
+            return None;
+        }
+        dfa.shortest_accepted()?;
+        Some(Regex::parse(&dfa.to_regex()).ok()?.simplify().to_string())
+    }
+}
+
+impl Regex {
+    /// Equivalent expression rewritten with the Kleene-algebra identities
+    /// `εr = rε = r`, `r+r = r`, `ε+r = r` for nullable `r`, `ε+rr* = r*`,
+    /// `(r*)* = r*`, `(ε+r)* = r*`, `r*r* = r*`, `ab+ac = a(b+c)` and
+    /// `ac+bc = (a+b)c`. Groups are dropped, so the result prints with the
+    /// fewest parentheses the syntax allows.
+    pub fn simplify(&self) -> Regex {
+        // Factoring can expose new identities, so rewrite until nothing
+        // changes; no rule makes the expression longer.
+        let mut current = simplified(self);
+        loop {
+            let next = simplified(&current);
+            if next == current {
+                return current;
+            }
+            current = next;
+        }
+    }
+
+    /// Whether the language contains the empty word.
+    pub fn is_nullable(&self) -> bool {
+        match self {
+            Regex::Epsilon | Regex::Star(_) => true,
+            Regex::Symbol(_) => false,
+            Regex::Concat(items) => items.iter().all(Regex::is_nullable),
+            Regex::Union(alternatives) => alternatives.iter().any(Regex::is_nullable),
+            Regex::Group(inner) => inner.is_nullable(),
+        }
+    }
+}
+
+fn simplified(regex: &Regex) -> Regex {
+    match regex {
+        Regex::Epsilon | Regex::Symbol(_) => regex.clone(),
+        Regex::Concat(items) => concat(items.iter().map(simplified).collect()),
+        Regex::Union(alternatives) => union(alternatives.iter().map(simplified).collect()),
+        Regex::Star(inner) => star(simplified(inner)),
+        Regex::Group(inner) => simplified(inner),
+    }
+}
+
+/// Sequence of factors, empty for `ε`.
+fn factors(regex: Regex) -> Vec<Regex> {
+    match regex {
+        Regex::Epsilon => vec![],
+        Regex::Concat(items) => items,
+        _ => vec![regex],
+    }
+}
+
+fn concat(items: Vec<Regex>) -> Regex {
+    let mut result: Vec<Regex> = vec![];
+    for item in items.into_iter().flat_map(factors) {
+        if matches!(item, Regex::Star(_)) && result.last() == Some(&item) {
+            continue;
+        }
+        result.push(item);
+    }
+    match result.len() {
+        0 => Regex::Epsilon,
+        1 => result.pop().unwrap(),
+        _ => Regex::Concat(result),
+    }
+}
+
+fn union(alternatives: Vec<Regex>) -> Regex {
+    let mut result: Vec<Regex> = vec![];
+    for alternative in alternatives {
+        let flattened = match alternative {
+            Regex::Union(inner) => inner,
+            _ => vec![alternative],
+        };
+        for alternative in flattened {
+            if !result.contains(&alternative) {
+                result.push(alternative);
+            }
+        }
+    }
+
+    if let Some(epsilon) = result.iter().position(|alternative| *alternative == Regex::Epsilon) {
+        if let Some(idx) = result.iter().position(|alternative| is_plus(alternative).is_some()) {
+            result[idx] = Regex::Star(Box::new(is_plus(&result[idx]).unwrap()));
+            result.remove(epsilon);
+        } else if result.iter().any(|alternative| *alternative != Regex::Epsilon && alternative.is_nullable()) {
+            result.remove(epsilon);
+        }
+    }
+
+    let result = factor(result, true);
+    let mut result = factor(result, false);
+    match result.len() {
+        0 => Regex::Epsilon,
+        1 => result.pop().unwrap(),
+        _ => Regex::Union(result),
+    }
+}
+
+fn star(inner: Regex) -> Regex {
+    match inner {
+        Regex::Epsilon => Regex::Epsilon,
+        Regex::Star(_) => inner,
+        // Inside a star, the empty word and nested stars add nothing:
+        // `(ε+r)* = r*`, `(r*+s)* = (r+s)*` and `(r*s*)* = (r+s)*`.
+        Regex::Union(alternatives) => {
+            let alternatives = alternatives.into_iter()
+                .filter(|alternative| *alternative != Regex::Epsilon)
+                .map(unstar)
+                .collect();
+            star_of(union(alternatives))
+        }
+        Regex::Concat(items) if items.iter().all(|item| matches!(item, Regex::Star(_))) => {
+            star_of(union(items.into_iter().map(unstar).collect()))
+        }
+        _ => Regex::Star(Box::new(inner)),
+    }
+}
+
+fn star_of(inner: Regex) -> Regex {
+    match inner {
+        Regex::Epsilon | Regex::Star(_) => star(inner),
+        _ => Regex::Star(Box::new(inner)),
+    }
+}
+
+fn unstar(regex: Regex) -> Regex {
+    match regex {
+        Regex::Star(inner) => *inner,
+        _ => regex,
+    }
+}
+
+/// `r` when `regex` is `rr*` or `r*r`.
+fn is_plus(regex: &Regex) -> Option<Regex> {
+    let Regex::Concat(items) = regex else {
+        return None;
+    };
+    if let Some(Regex::Star(inner)) = items.last() {
+        if concat(items[..items.len() - 1].to_vec()) == **inner {
+            return Some((**inner).clone());
+        }
+    }
+    if let Some(Regex::Star(inner)) = items.first() {
+        if concat(items[1..].to_vec()) == **inner {
+            return Some((**inner).clone());
+        }
+    }
+    None
+}
+
+/// Merges alternatives sharing their first (or, when `prefix` is false,
+/// last) factor: `ab+ac = a(b+c)`. Merged alternatives take the place of
+/// the first one of their group.
+fn factor(alternatives: Vec<Regex>, prefix: bool) -> Vec<Regex> {
+    let mut groups: Vec<(Option<Regex>, Vec<Vec<Regex>>)> = vec![];
+    for alternative in alternatives {
+        let rest = factors(alternative);
+        let key = if prefix { rest.first().cloned() } else { rest.last().cloned() };
+        if key.is_some() {
+            if let Some((_, members)) = groups.iter_mut().find(|(other, _)| *other == key) {
+                members.push(rest);
+                continue;
+            }
+        }
+        groups.push((key, vec![rest]));
+    }
+
+    groups.into_iter()
+        .map(|(key, mut members)| {
+            if members.len() == 1 {
+                return concat(members.pop().unwrap());
+            }
+            let key = key.unwrap();
+            let remainders = members.into_iter()
+                .map(|mut rest| {
+                    if prefix {
+                        rest.remove(0);
+                    } else {
+                        rest.pop();
+                    }
+                    concat(rest)
+                })
+                .collect();
+            if prefix {
+                concat(vec![key, union(remainders)])
+            } else {
+                concat(vec![union(remainders), key])
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
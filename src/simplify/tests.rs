@@ -0,0 +1,119 @@
+use crate::automaton::*;
+use crate::language::*;
+use crate::nfa::*;
+use crate::regex::*;
+use crate::simplify::*;
+use crate::testing::*;
+
+fn simplify(pattern: &str) -> String {
+    Regex::parse(pattern).unwrap().simplify().to_string()
+}
+
+#[test]
+fn simplify_identities_test() {
+    assert_eq!(simplify("(b)*a((a+b))*"), "b*a(a+b)*");
+    assert_eq!(simplify("(a)(b)"), "ab");
+    assert_eq!(simplify("()a()"), "a");
+    assert_eq!(simplify("a+b+a"), "a+b");
+    assert_eq!(simplify("(a*)*"), "a*");
+    assert_eq!(simplify("a**"), "a*");
+    assert_eq!(simplify("()*"), "");
+    assert_eq!(simplify("(+a)*"), "a*");
+    assert_eq!(simplify("(a*b*)*"), "(a+b)*");
+    assert_eq!(simplify("a*a*"), "a*");
+    assert_eq!(simplify("+a*"), "a*");
+    assert_eq!(simplify("+aa*"), "a*");
+    assert_eq!(simplify("(+a)b"), "(+a)b");
+}
+
+#[test]
+fn simplify_factoring_test() {
+    assert_eq!(simplify("ab+ac"), "a(b+c)");
+    assert_eq!(simplify("ac+bc"), "(a+b)c");
+    assert_eq!(simplify("abd+acd"), "a(b+c)d");
+    assert_eq!(simplify("ab+a"), "a(b+)");
+    assert_eq!(simplify("ab+cd"), "ab+cd");
+    assert_eq!(simplify("ab+(a)b"), "ab");
+}
+
+#[test]
+fn simplify_is_fixpoint_test() {
+    for pattern in ["(b)*a((a+b))*", "bb+(a+ba)(a)*b", "ab+ac+bc", "((a+)*b+(b))*"] {
+        let simplified = Regex::parse(pattern).unwrap().simplify();
+        assert_eq!(simplified.simplify(), simplified);
+        assert_eq!(Regex::parse(&simplified.to_string()).unwrap().simplify(), simplified);
+    }
+}
+
+#[test]
+fn simplify_preserves_language_test() {
+    for pattern in ["(b)*a((a+b))*", "bb+(a+ba)(a)*b", "ab+ac+bc", "((a+)*b+(b))*",
+                    "+aa*+b", "(a*b*)*c", "ab+a+abb", "(ab+b)*(ab+b)*"] {
+        let original = from_regex(pattern);
+        let simplified = from_regex(&simplify(pattern));
+        assert_eq!(original.check_equivalence(&simplified), Equivalence::Equivalent, "{pattern}");
+    }
+}
+
+#[test]
+fn to_simplified_regex_test() {
+    let mut q0: NfaState<String> = NfaState::new(0, false);
+    q0.add_transition(String::from("a"), 1);
+    q0.add_transition(String::from("b"), 0);
+
+    let mut q1: NfaState<String> = NfaState::new(1, true);
+    q1.add_transition(String::from("a"), 1);
+    q1.add_transition(String::from("b"), 1);
+
+    let mut nfa: Nfa<String> = Nfa::new(0);
+    nfa.add_state(q0);
+    nfa.add_state(q1);
+
+    assert_eq!(nfa.to_regex(), "(b)*a((a+b))*");
+    assert_eq!(nfa.to_simplified_regex().unwrap(), "b*a(a+b)*");
+}
+
+#[test]
+fn to_simplified_regex_equivalence_test() {
+    let mut patterns: Vec<String> = ["(baa)+ba", "bb+(a+ba)(a)*b", "(b)*a((a+b))*", "a(b+)(+a)b",
+                                     "(ab+b)*(+a)", "((a+b)b)*a+b", "(a+)(b+)(a+b)"]
+        .iter().map(|pattern| pattern.to_string()).collect();
+    let parts = ["a", "b", "", "ab", "a*", "(a+b)", "(ba)*", "(+a)"];
+    for x in parts {
+        for y in parts {
+            patterns.push(format!("{x}+{y}"));
+            patterns.push(format!("({x}+{y})b{y}"));
+            patterns.push(format!("({x}+b)*{y}+a"));
+        }
+    }
+    for pattern in patterns {
+        let source = from_regex(&pattern);
+        let regex = source.to_simplified_regex().unwrap();
+        let parsed = from_regex(&regex);
+        assert_eq!(source.check_equivalence(&parsed), Equivalence::Equivalent, "{pattern} -> {regex}");
+    }
+}
+
+#[test]
+fn to_simplified_regex_operator_symbol_test() {
+    let mut q0: NfaState<String> = NfaState::new(0, false);
+    q0.add_transition(String::from("a+b"), 1);
+    let mut nfa: Nfa<String> = Nfa::new(0);
+    nfa.add_state(q0);
+    nfa.add_state(NfaState::new(1, true));
+
+    assert_eq!(nfa.to_simplified_regex(), None);
+    assert_eq!(nfa.to_dfa().to_simplified_regex(), None);
+}
+
+#[test]
+fn to_simplified_regex_empty_language_test() {
+    let mut q0: NfaState<String> = NfaState::new(0, false);
+    q0.add_transition(String::from("a"), 1);
+    let mut nfa: Nfa<String> = Nfa::new(0);
+    nfa.add_state(q0);
+    nfa.add_state(NfaState::new(1, false));
+
+    assert_eq!(nfa.to_simplified_regex(), None);
+    assert_eq!(nfa.to_dfa().to_simplified_regex(), None);
+}
@@ -1,6 +1,7 @@
 use crate::state::*;
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn string_traversal_test() {
     assert_eq!(String::from("ab").go("abv").unwrap(), "v");
     assert_eq!(String::from("bd").go("bd").unwrap(), "");
@@ -14,6 +15,7 @@ fn string_traversal_test() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn char_traversal_test() {
     assert_eq!('a'.go("abv").unwrap(), "bv");
     assert_eq!('b'.go("bd").unwrap(), "d");
@@ -0,0 +1,30 @@
+//! Helpers shared by the unit tests.
+
+use crate::dfa::{Dfa, ToDfa};
+use crate::nfa::Nfa;
+
+/// `Nfa::from_regex` for patterns known to be valid.
+pub(crate) fn from_regex(pattern: &str) -> Nfa<String> {
+    Nfa::from_regex(pattern).unwrap()
+}
+
+pub(crate) fn dfa_from_regex(pattern: &str) -> Dfa {
+    from_regex(pattern).to_dfa()
+}
+
+/// Every word over `alphabet` of at most `max_len` symbols, shortest first.
+pub(crate) fn words(alphabet: &[char], max_len: usize) -> Vec<String> {
+    let mut result = vec![String::new()];
+    let mut layer = vec![String::new()];
+    for _ in 0..max_len {
+        let mut next = vec![];
+        for word in &layer {
+            for ch in alphabet {
+                next.push(format!("{word}{ch}"));
+            }
+        }
+        result.extend(next.iter().cloned());
+        layer = next;
+    }
+    result
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+use crate::parse::{ParseError, RawAutomaton, RawTransition};
+
+/// Escapes a transition label for the `Display` format of `Nfa` and `Dfa`.
+pub(crate) fn escape(word: &str) -> String {
+    let mut result = String::new();
+    for ch in word.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            _ => result.push(ch),
+        }
+    }
+    result
+}
+
+struct Line {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize
+}
+
+impl Line {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(self.line, self.pos + 1, message)
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|ch| ch.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, text: &str) -> Result<(), ParseError> {
+        self.skip_spaces();
+        for ch in text.chars() {
+            if self.chars.get(self.pos) != Some(&ch) {
+                return Err(self.error(&format!("expected \"{text}\"")));
+            }
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<usize, ParseError> {
+        self.skip_spaces();
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|ch| ch.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse().map_err(|_| {
+            self.pos = start;
+            self.error("expected state index")
+        })
+    }
+
+    fn quoted(&mut self) -> Result<String, ParseError> {
+        self.expect("\"")?;
+        let mut result = String::new();
+        loop {
+            let Some(ch) = self.chars.get(self.pos).copied() else {
+                return Err(self.error("unterminated label"));
+            };
+            self.pos += 1;
+            match ch {
+                '"' => return Ok(result),
+                '\\' => {
+                    let escaped = match self.chars.get(self.pos) {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        _ => {
+                            self.pos -= 1;
+                            return Err(self.error("unknown escape sequence"));
+                        }
+                    };
+                    self.pos += 1;
+                    result.push(escaped);
+                }
+                _ => result.push(ch),
+            }
+        }
+    }
+
+    fn end(&mut self) -> Result<(), ParseError> {
+        self.skip_spaces();
+        if self.pos < self.chars.len() {
+            return Err(self.error("unexpected text at end of line"));
+        }
+        Ok(())
+    }
+}
+
+/// Reads the format written by the `Display` implementations of `Nfa` and
+/// `Dfa`. Blank lines are ignored.
+fn read_text(src: &str) -> Result<RawAutomaton, ParseError> {
+    let mut lines = src.lines()
+        .enumerate()
+        .map(|(i, text)| Line{chars: text.chars().collect(), pos: 0, line: i + 1})
+        .filter(|line| line.chars.iter().any(|ch| !ch.is_whitespace()));
+
+    let Some(mut first) = lines.next() else {
+        return Err(ParseError::new(1, 1, "expected \"Starting state:\""));
+    };
+    first.expect("Starting state:")?;
+    let starting_state = first.number()?;
+    first.end()?;
+
+    let mut states: BTreeMap<usize, bool> = BTreeMap::from([(starting_state, false)]);
+    let mut transitions = vec![];
+    let mut terminal_section = false;
+    let mut last_line = first.line;
+    for mut line in lines {
+        last_line = line.line;
+        if terminal_section {
+            let idx = line.number()?;
+            line.end()?;
+            states.insert(idx, true);
+            continue;
+        }
+        if line.chars.starts_with(&['T']) {
+            line.expect("Terminal states:")?;
+            line.end()?;
+            terminal_section = true;
+            continue;
+        }
+        let column = line.pos + 1;
+        let from = line.number()?;
+        line.expect("->")?;
+        let to = line.number()?;
+        line.expect("by")?;
+        let word = line.quoted()?;
+        line.end()?;
+        states.entry(from).or_insert(false);
+        states.entry(to).or_insert(false);
+        transitions.push(RawTransition{from, word, to, line: line.line, column});
+    }
+    if !terminal_section {
+        return Err(ParseError::new(last_line + 1, 1, "expected \"Terminal states:\""));
+    }
+    Ok(RawAutomaton{starting_state, states, transitions})
+}
+
+impl FromStr for Nfa<String> {
+    type Err = ParseError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Ok(read_text(src)?.into_nfa())
+    }
+}
+
+impl FromStr for Nfa<char> {
+    type Err = ParseError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        read_text(src)?.into_char_nfa()
+    }
+}
+
+impl FromStr for Dfa {
+    type Err = ParseError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        read_text(src)?.into_dfa()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,78 @@
+use crate::automaton::*;
+use crate::dfa::*;
+use crate::language::*;
+use crate::nfa::*;
+use crate::parse::ParseError;
+use crate::testing::*;
+
+#[test]
+fn nfa_text_round_trip_test() {
+    let mut q0: NfaState<String> = NfaState::new(0, false);
+    q0.add_transition(String::from("say \"hi\""), 1);
+    q0.add_transition(String::from("a\\b"), 2);
+    q0.add_transition(String::from(""), 2);
+    let mut q1: NfaState<String> = NfaState::new(1, true);
+    q1.add_transition(String::from("line\nbreak\t"), 0);
+    let q2: NfaState<String> = NfaState::new(2, true);
+    let mut nfa: Nfa<String> = Nfa::new(0);
+    nfa.add_state(q0);
+    nfa.add_state(q1);
+    nfa.add_state(q2);
+
+    let text = nfa.to_string();
+    assert!(text.contains("0 -> 1 by \"say \\\"hi\\\"\""));
+    assert!(text.contains("1 -> 0 by \"line\\nbreak\\t\""));
+
+    let parsed: Nfa<String> = text.parse().unwrap();
+    assert_eq!(parsed.to_string(), text);
+    assert!(parsed.accept("say \"hi\"line\nbreak\t"));
+    assert!(parsed.accept("a\\b"));
+    assert!(parsed.accept(""));
+}
+
+#[test]
+fn dfa_text_round_trip_test() {
+    let dfa = from_regex("(a+\")*\\").to_mcdfa();
+    let text = dfa.to_string();
+
+    let parsed: Dfa = text.parse().unwrap();
+    assert_eq!(parsed.to_string(), text);
+    assert!(matches!(parsed.check_equivalence(&dfa), Equivalence::Equivalent));
+}
+
+#[test]
+fn char_nfa_from_text_test() {
+    let nfa: Nfa<char> = "\
+Starting state: 0
+
+0 -> 1 by \"a\"
+0 -> 2 by \"a\"
+2 -> 2 by \"b\"
+Terminal states:
+1
+2
+".parse().unwrap();
+    assert!(nfa.accept("a"));
+    assert!(nfa.accept("abb"));
+    assert!(!nfa.accept("b"));
+}
+
+#[test]
+fn text_error_position_test() {
+    let error: ParseError = "Starting state: 0\n0 -> 1 by \"a\\q\"\nTerminal states:\n"
+        .parse::<Nfa<String>>().err().unwrap();
+    assert_eq!(error.to_string(), "2:13: unknown escape sequence");
+
+    let error = "Starting state: 0\n0 -> x by \"a\"\n".parse::<Nfa<String>>().err().unwrap();
+    assert_eq!((error.line, error.column), (2, 6));
+
+    let error = "Starting state: 0\n0 -> 1 by \"a\n".parse::<Nfa<String>>().err().unwrap();
+    assert_eq!(error.message, "unterminated label");
+
+    let error = "Starting state: 0\n0 -> 1 by \"a\"\n".parse::<Nfa<String>>().err().unwrap();
+    assert_eq!((error.line, error.column), (3, 1));
+
+    let error = "Starting state: 0\n0 -> 1 by \"a\"\n0 -> 0 by \"a\"\nTerminal states:\n"
+        .parse::<Dfa>().err().unwrap();
+    assert_eq!((error.line, error.column), (3, 1));
+}
@@ -0,0 +1,427 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::Range;
+
+use crate::nfa::{Nfa, NfaState};
+use crate::regex::{Regex, RegexError};
+use crate::text::escape;
+
+/// Transition label of a `Tnfa`: either a word to read, or a tag that
+/// records the current input position in a slot without reading anything.
+/// Slot `2·k` is where group `k` starts and slot `2·k + 1` where it ends.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TnfaLabel {
+    Word(String),
+    Tag(usize),
+}
+
+#[derive(Clone)]
+pub struct TnfaState {
+    pub index: usize,
+    pub is_terminal: bool,
+    pub(crate) transitions: Vec<(TnfaLabel, usize)>
+}
+
+impl TnfaState {
+    pub fn new(index: usize, is_terminal: bool) -> Self {
+        Self{index, is_terminal, transitions: Vec::new()}
+    }
+
+    pub fn add_transition(&mut self, label: TnfaLabel, next_state: usize) {
+        self.transitions.push((label, next_state));
+    }
+}
+
+/// Tagged NFA: an `Nfa<String>` whose transitions may also be tags, used to
+/// extract capture groups. Group 0 is the whole match and has no tags.
+#[derive(Clone)]
+pub struct Tnfa {
+    pub starting_state: usize,
+    pub states: BTreeMap<usize, TnfaState>,
+    /// Number of groups including group 0.
+    pub group_count: usize,
+    /// Innermost group enclosing each group; `None` for group 0.
+    pub parents: Vec<Option<usize>>
+}
+
+/// How to choose between the ways a regex can match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Disambiguation {
+    /// The overall match is the leftmost-longest one. Among its parses,
+    /// groups are compared in order: an earlier start wins, then a longer
+    /// span, and a group that matched wins over one that did not. Runs are
+    /// compared where they meet, on the tags recorded so far.
+    #[default]
+    Posix,
+    /// The parse a backtracking matcher finds first: transitions leaving a
+    /// state are tried in the order they were added, and stopping at a
+    /// terminal state comes after all of them.
+    LeftmostFirst,
+}
+
+/// Spans of the groups of one match, as byte ranges of the haystack.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Captures {
+    spans: Vec<Option<Range<usize>>>
+}
+
+impl Captures {
+    /// Span of group `group`, `None` when it did not take part in the match.
+    pub fn get(&self, group: usize) -> Option<Range<usize>> {
+        self.spans.get(group).cloned().flatten()
+    }
+
+    /// Text of group `group` in `haystack`.
+    pub fn extract<'h>(&self, haystack: &'h str, group: usize) -> Option<&'h str> {
+        self.get(group).map(|span| &haystack[span])
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
+
+impl Tnfa {
+    /// Every group but group 0 starts out directly inside group 0.
+    pub fn new(starting_state: usize, group_count: usize) -> Self {
+        let parents = (0..group_count).map(|group| (group > 0).then_some(0)).collect();
+        Self{starting_state, states: BTreeMap::new(), group_count, parents}
+    }
+
+    /// Declares `group` to be nested directly inside `parent`.
+    pub fn set_parent(&mut self, group: usize, parent: usize) {
+        self.parents[group] = Some(parent);
+    }
+
+    pub fn from_regex(pattern: &str) -> Result<Self, RegexError> {
+        Ok(Regex::parse(pattern)?.to_tnfa())
+    }
+
+    pub fn add_state(&mut self, state: TnfaState) {
+        if self.states.contains_key(&state.index) {
+            panic!("State index duplicate!");
+        }
+        self.states.insert(state.index, state);
+    }
+
+    /// The same automaton with tags turned into empty-word transitions.
+    pub fn to_nfa(&self) -> Nfa<String> {
+        let mut nfa: Nfa<String> = Nfa::new(self.starting_state);
+        for state in self.states.values() {
+            let mut nfa_state = NfaState::new(state.index, state.is_terminal);
+            for (label, next_state) in state.transitions.iter() {
+                match label {
+                    TnfaLabel::Word(word) => nfa_state.add_transition(word.clone(), *next_state),
+                    TnfaLabel::Tag(_) => nfa_state.add_transition(String::new(), *next_state),
+                }
+            }
+            nfa.add_state(nfa_state);
+        }
+        nfa
+    }
+
+    /// Groups of a match of the whole `haystack`.
+    pub fn captures(&self, haystack: &str, mode: Disambiguation) -> Option<Captures> {
+        Pike::new(self, mode).run(haystack, true)
+    }
+
+    /// Groups of the leftmost match in `haystack`.
+    pub fn find_captures(&self, haystack: &str, mode: Disambiguation) -> Option<Captures> {
+        Pike::new(self, mode).run(haystack, false)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Edge {
+    Epsilon(usize),
+    Tag(usize, usize),
+    Symbol(char, usize),
+}
+
+/// Recorded positions, indexed by slot.
+type Slots = Vec<Option<usize>>;
+
+/// Ordered choice of a run at the current position, as in `search`.
+enum Leaf {
+    Symbol(char, usize, Slots),
+    Match(Slots),
+}
+
+/// Order of `Disambiguation::Posix`, `Greater` when `a` is preferred. A
+/// group that started but has not ended yet is still growing, so it beats
+/// any end.
+fn compare_posix(a: &Slots, b: &Slots) -> Ordering {
+    for pair in 0..a.len() / 2 {
+        let order = match (a[2 * pair], b[2 * pair]) {
+            (Some(x), Some(y)) => y.cmp(&x),
+            (x, y) => x.is_some().cmp(&y.is_some()),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+        let end = |slot: Option<usize>| slot.unwrap_or(usize::MAX);
+        let order = end(a[2 * pair + 1]).cmp(&end(b[2 * pair + 1]));
+        if a[2 * pair].is_some() && order != Ordering::Equal {
+            return order;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Pike VM over the tagged automaton: all runs advance in lock step and
+/// carry their own slots, so the time is linear in the haystack. Words are
+/// split into chains of single symbols.
+struct Pike {
+    mode: Disambiguation,
+    slot_count: usize,
+    /// Groups nested at any depth inside each group.
+    inner: Vec<Vec<usize>>,
+    starting_state: Option<usize>,
+    is_terminal: Vec<bool>,
+    edges: Vec<Vec<Edge>>
+}
+
+impl Pike {
+    fn new(tnfa: &Tnfa, mode: Disambiguation) -> Self {
+        let dense: HashMap<usize, usize> = tnfa.states.keys().enumerate().map(|(i, idx)| (*idx, i)).collect();
+        let mut is_terminal: Vec<bool> = tnfa.states.values().map(|state| state.is_terminal).collect();
+        let mut edges: Vec<Vec<Edge>> = vec![vec![]; tnfa.states.len()];
+        for (i, state) in tnfa.states.values().enumerate() {
+            for (label, next_state) in state.transitions.iter() {
+                let Some(&target) = dense.get(next_state) else {
+                    continue;
+                };
+                let chars: Vec<char> = match label {
+                    TnfaLabel::Tag(slot) => {
+                        edges[i].push(Edge::Tag(*slot, target));
+                        continue;
+                    }
+                    TnfaLabel::Word(word) => word.chars().collect(),
+                };
+                if chars.is_empty() {
+                    edges[i].push(Edge::Epsilon(target));
+                    continue;
+                }
+                let mut cur = i;
+                for (j, ch) in chars.iter().enumerate() {
+                    let next = if j == chars.len() - 1 {
+                        target
+                    } else {
+                        edges.push(vec![]);
+                        is_terminal.push(false);
+                        edges.len() - 1
+                    };
+                    edges[cur].push(Edge::Symbol(*ch, next));
+                    cur = next;
+                }
+            }
+        }
+        let mut inner: Vec<Vec<usize>> = vec![vec![]; tnfa.group_count.max(1)];
+        for group in 1..tnfa.group_count {
+            let mut ancestor = tnfa.parents.get(group).copied().flatten();
+            while let Some(parent) = ancestor {
+                inner[parent].push(group);
+                ancestor = tnfa.parents.get(parent).copied().flatten();
+            }
+        }
+        Self{mode, slot_count: 2 * tnfa.group_count.max(1), inner,
+             starting_state: dense.get(&tnfa.starting_state).copied(), is_terminal, edges}
+    }
+
+    fn write(&self, slots: &Slots, slot: usize, pos: usize) -> Slots {
+        let mut slots = slots.clone();
+        if slot < slots.len() {
+            slots[slot] = Some(pos);
+            // Entering a group again forgets where it ended last time and,
+            // under POSIX, everything its inner groups matched in the
+            // previous iteration.
+            if slot.is_multiple_of(2) && slot + 1 < slots.len() {
+                slots[slot + 1] = None;
+                if self.mode == Disambiguation::Posix {
+                    for group in self.inner[slot / 2].iter() {
+                        slots[2 * group] = None;
+                        slots[2 * group + 1] = None;
+                    }
+                }
+            }
+        }
+        slots
+    }
+
+    /// Choices in priority order; a state reached twice keeps its first run.
+    fn closure_first(&self, entered: Vec<(usize, Slots)>, pos: usize, leaves: &mut Vec<Leaf>) {
+        let mut seen = vec![false; self.edges.len()];
+        let mut stack: Vec<(usize, usize, Slots)> = vec![];
+        for (state, slots) in entered {
+            if seen[state] {
+                continue;
+            }
+            seen[state] = true;
+            stack.push((state, 0, slots));
+            while let Some((cur, i, slots)) = stack.pop() {
+                let Some(edge) = self.edges[cur].get(i).copied() else {
+                    if self.is_terminal[cur] {
+                        leaves.push(Leaf::Match(slots));
+                    }
+                    continue;
+                };
+                let child = match edge {
+                    Edge::Epsilon(next) => Some((next, slots.clone())),
+                    Edge::Tag(slot, next) => Some((next, self.write(&slots, slot, pos))),
+                    Edge::Symbol(ch, next) => {
+                        leaves.push(Leaf::Symbol(ch, next, slots.clone()));
+                        None
+                    }
+                };
+                stack.push((cur, i + 1, slots));
+                if let Some((next, child_slots)) = child {
+                    if !seen[next] {
+                        seen[next] = true;
+                        stack.push((next, 0, child_slots));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Best slots of every state reachable without reading, improved until
+    /// nothing changes.
+    fn closure_posix(&self, entered: Vec<(usize, Slots)>, pos: usize, leaves: &mut Vec<Leaf>) {
+        let mut best: Vec<Option<Slots>> = vec![None; self.edges.len()];
+        let mut queue = vec![];
+        let offer = |best: &mut Vec<Option<Slots>>, queue: &mut Vec<usize>, state: usize, slots: Slots| {
+            let better = match &best[state] {
+                None => true,
+                Some(current) => compare_posix(&slots, current) == Ordering::Greater,
+            };
+            if better {
+                best[state] = Some(slots);
+                queue.push(state);
+            }
+        };
+        for (state, slots) in entered {
+            offer(&mut best, &mut queue, state, slots);
+        }
+        while let Some(cur) = queue.pop() {
+            let slots = best[cur].clone().unwrap();
+            for edge in self.edges[cur].iter() {
+                match *edge {
+                    Edge::Epsilon(next) => offer(&mut best, &mut queue, next, slots.clone()),
+                    Edge::Tag(slot, next) => offer(&mut best, &mut queue, next, self.write(&slots, slot, pos)),
+                    Edge::Symbol(..) => {}
+                }
+            }
+        }
+        for (state, slots) in best.into_iter().enumerate() {
+            let Some(slots) = slots else {
+                continue;
+            };
+            for edge in self.edges[state].iter() {
+                if let Edge::Symbol(ch, next) = *edge {
+                    leaves.push(Leaf::Symbol(ch, next, slots.clone()));
+                }
+            }
+            if self.is_terminal[state] {
+                leaves.push(Leaf::Match(slots));
+            }
+        }
+    }
+
+    fn run(&self, haystack: &str, anchored: bool) -> Option<Captures> {
+        let start = self.starting_state?;
+        let mut threads: Vec<(usize, Slots)> = vec![];
+        let mut best: Option<Slots> = None;
+        let mut pos = 0;
+        loop {
+            if best.is_none() && (pos == 0 || !anchored) {
+                let mut slots = vec![None; self.slot_count];
+                slots[0] = Some(pos);
+                threads.push((start, slots));
+            }
+            if threads.is_empty() {
+                break;
+            }
+            let mut leaves = vec![];
+            match self.mode {
+                Disambiguation::LeftmostFirst => self.closure_first(std::mem::take(&mut threads), pos, &mut leaves),
+                Disambiguation::Posix => self.closure_posix(std::mem::take(&mut threads), pos, &mut leaves),
+            }
+            let ch = haystack[pos..].chars().next();
+            for leaf in leaves {
+                match leaf {
+                    Leaf::Match(mut slots) => {
+                        if anchored && ch.is_some() {
+                            continue;
+                        }
+                        slots[1] = Some(pos);
+                        // A later match starts no later than the current
+                        // best, so it is either more to the left, longer, or
+                        // of a higher priority.
+                        let better = match (&best, self.mode) {
+                            (None, _) | (_, Disambiguation::LeftmostFirst) => true,
+                            (Some(current), Disambiguation::Posix) => {
+                                slots[0] < current[0] || slots[1] > current[1]
+                                    || compare_posix(&slots, current) == Ordering::Greater
+                            }
+                        };
+                        if better {
+                            best = Some(slots);
+                        }
+                        if self.mode == Disambiguation::LeftmostFirst {
+                            break;
+                        }
+                    }
+                    Leaf::Symbol(symbol, next, slots) => {
+                        if Some(symbol) == ch {
+                            threads.push((next, slots));
+                        }
+                    }
+                }
+            }
+            if let Some(current) = &best {
+                threads.retain(|(_, slots)| slots[0] <= current[0]);
+            }
+            match ch {
+                Some(ch) => pos += ch.len_utf8(),
+                None => break,
+            }
+        }
+        best.map(|slots| Captures{
+            spans: (0..slots.len() / 2)
+                .map(|group| match (slots[2 * group], slots[2 * group + 1]) {
+                    (Some(start), Some(end)) if start <= end => Some(start..end),
+                    _ => None,
+                })
+                .collect()
+        })
+    }
+}
+
+impl fmt::Display for Tnfa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Starting state: {}", self.starting_state)?;
+        for (idx, state) in self.states.iter() {
+            for (label, next_state) in state.transitions.iter() {
+                match label {
+                    TnfaLabel::Word(word) => writeln!(f, "{idx} -> {next_state} by \"{}\"", escape(word))?,
+                    TnfaLabel::Tag(slot) => writeln!(f, "{idx} -> {next_state} tag {slot}")?,
+                }
+            }
+        }
+        writeln!(f, "Terminal states:")?;
+        for (idx, state) in self.states.iter() {
+            if state.is_terminal {
+                writeln!(f, "{idx}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
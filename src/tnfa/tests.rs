@@ -0,0 +1,102 @@
+use crate::automaton::*;
+use crate::language::*;
+use crate::regex::Regex;
+use crate::tnfa::*;
+
+fn spans(tnfa: &Tnfa, haystack: &str, mode: Disambiguation) -> Vec<Option<(usize, usize)>> {
+    let captures = tnfa.captures(haystack, mode).unwrap();
+    (0..captures.len()).map(|group| captures.get(group).map(|span| (span.start, span.end))).collect()
+}
+
+#[test]
+fn tnfa_from_regex_test() {
+    let regex = Regex::parse("(a(b))*+c").unwrap();
+    let tnfa = regex.to_tnfa();
+    assert_eq!(tnfa.group_count, 3);
+    assert!(matches!(tnfa.to_nfa().check_equivalence(&regex.to_nfa()), Equivalence::Equivalent));
+    assert!(tnfa.to_nfa().accept("abab"));
+    assert!(tnfa.to_string().contains(" tag 2\n"));
+    assert!(tnfa.to_string().contains(" tag 5\n"));
+}
+
+#[test]
+fn captures_test() {
+    // user@host:port, with an optional port; `+` is union.
+    let tnfa = Tnfa::from_regex("((a+b)(a+b)*)@((a+b+.)*)(:((0+1)(0+1)*)+)").unwrap();
+    let haystack = "ab@a.b:101";
+    let captures = tnfa.captures(haystack, Disambiguation::Posix).unwrap();
+    assert_eq!(captures.extract(haystack, 1), Some("ab"));
+    assert_eq!(captures.extract(haystack, 4), Some("a.b"));
+    assert_eq!(captures.extract(haystack, 7), Some("101"));
+    assert_eq!(captures.get(0), Some(0..10));
+
+    let captures = tnfa.captures("b@a", Disambiguation::LeftmostFirst).unwrap();
+    assert_eq!(captures.get(4), Some(2..3));
+    assert_eq!(captures.get(6), Some(3..3));
+    assert_eq!(captures.get(7), None);
+    assert_eq!(captures.len(), 10);
+    assert!(tnfa.captures("b@a:2", Disambiguation::Posix).is_none());
+}
+
+#[test]
+fn disambiguation_test() {
+    let tnfa = Tnfa::from_regex("(a+ab)(c+bcd)(d*)").unwrap();
+    assert_eq!(spans(&tnfa, "abcd", Disambiguation::Posix),
+               vec![Some((0, 4)), Some((0, 2)), Some((2, 3)), Some((3, 4))]);
+    assert_eq!(spans(&tnfa, "abcd", Disambiguation::LeftmostFirst),
+               vec![Some((0, 4)), Some((0, 1)), Some((1, 4)), Some((4, 4))]);
+
+    // The first group takes as much as it can.
+    let tnfa = Tnfa::from_regex("(a*)(a*)").unwrap();
+    assert_eq!(spans(&tnfa, "aaa", Disambiguation::Posix), vec![Some((0, 3)), Some((0, 3)), Some((3, 3))]);
+    assert_eq!(spans(&tnfa, "aaa", Disambiguation::LeftmostFirst), vec![Some((0, 3)), Some((0, 3)), Some((3, 3))]);
+
+    // A repeated group reports its last iteration.
+    let tnfa = Tnfa::from_regex("(a+b)*").unwrap();
+    assert_eq!(spans(&tnfa, "aab", Disambiguation::Posix), vec![Some((0, 3)), Some((2, 3))]);
+    assert_eq!(spans(&tnfa, "", Disambiguation::Posix), vec![Some((0, 0)), None]);
+
+    // Groups inside a repeated group only report the last iteration too.
+    let tnfa = Tnfa::from_regex("((a)+b)*").unwrap();
+    assert_eq!(tnfa.parents, vec![None, Some(0), Some(1)]);
+    assert_eq!(spans(&tnfa, "ab", Disambiguation::Posix), vec![Some((0, 2)), Some((1, 2)), None]);
+    assert_eq!(spans(&tnfa, "ba", Disambiguation::Posix), vec![Some((0, 2)), Some((1, 2)), Some((1, 2))]);
+    let tnfa = Tnfa::from_regex("(((a)+b)c)*").unwrap();
+    assert_eq!(spans(&tnfa, "acbc", Disambiguation::Posix),
+               vec![Some((0, 4)), Some((2, 4)), Some((2, 3)), None]);
+}
+
+#[test]
+fn find_captures_test() {
+    let tnfa = Tnfa::from_regex("(a+ab)(b*)").unwrap();
+    let posix = tnfa.find_captures("xxabbz", Disambiguation::Posix).unwrap();
+    assert_eq!(posix.get(0), Some(2..5));
+    assert_eq!(posix.get(1), Some(2..4));
+    assert_eq!(posix.get(2), Some(4..5));
+
+    let first = tnfa.find_captures("xxabbz", Disambiguation::LeftmostFirst).unwrap();
+    assert_eq!(first.get(0), Some(2..5));
+    assert_eq!(first.get(1), Some(2..3));
+    assert_eq!(first.get(2), Some(3..5));
+    assert!(tnfa.find_captures("zzz", Disambiguation::Posix).is_none());
+}
+
+#[test]
+fn hand_built_tnfa_test() {
+    // 0 -tag 2-> 1 -"日本"-> 1 -tag 3-> 2, with 2 terminal.
+    let mut q0 = TnfaState::new(0, false);
+    q0.add_transition(TnfaLabel::Tag(2), 1);
+    let mut q1 = TnfaState::new(1, false);
+    q1.add_transition(TnfaLabel::Word(String::from("日本")), 1);
+    q1.add_transition(TnfaLabel::Tag(3), 2);
+    let mut tnfa = Tnfa::new(0, 2);
+    tnfa.add_state(q0);
+    tnfa.add_state(q1);
+    tnfa.add_state(TnfaState::new(2, true));
+
+    let haystack = "の日本日本";
+    let captures = tnfa.find_captures(haystack, Disambiguation::Posix).unwrap();
+    assert_eq!(captures.get(0), Some(0..0));
+    let captures = tnfa.captures(&haystack[3..], Disambiguation::LeftmostFirst).unwrap();
+    assert_eq!(captures.get(1), Some(0..12));
+}
@@ -0,0 +1,131 @@
+use crate::dfa::ToDfa;
+
+/// Iterator over accepted words in shortlex order: by length first, then
+/// lexicographically by symbol. It ends on its own when the language is
+/// finite.
+pub struct Words {
+    starting_state: Option<usize>,
+    is_terminal: Vec<bool>,
+    transitions: Vec<Vec<(char, usize)>>,
+    /// `alive[r][s]` tells whether state `s` reaches a terminal state by a
+    /// word of exactly `r` symbols.
+    alive: Vec<Vec<bool>>,
+    len: usize,
+    max_len: Option<usize>,
+    limit: Option<usize>,
+    produced: usize,
+    started: bool,
+    exhausted: bool,
+    stack: Vec<(usize, usize)>,
+    word: Vec<char>
+}
+
+pub trait Enumerate {
+    fn words(&self) -> Words;
+}
+
+impl<T: ToDfa> Enumerate for T {
+    fn words(&self) -> Words {
+        let trimmed = self.to_dfa().trimmed();
+        Words{starting_state: trimmed.starting_state, is_terminal: trimmed.is_terminal,
+              transitions: trimmed.transitions, alive: vec![], len: 0, max_len: None, limit: None,
+              produced: 0, started: false, exhausted: false, stack: vec![], word: vec![]}
+    }
+}
+
+impl Words {
+    /// Skips the words shorter than `len`.
+    pub fn min_len(mut self, len: usize) -> Self {
+        self.len = len;
+        self
+    }
+
+    /// Stops before the first word longer than `len`.
+    pub fn max_len(mut self, len: usize) -> Self {
+        self.max_len = Some(len);
+        self
+    }
+
+    /// Stops after `count` words.
+    pub fn limit(mut self, count: usize) -> Self {
+        self.limit = Some(count);
+        self
+    }
+
+    fn alive(&mut self, r: usize) -> &[bool] {
+        if self.alive.is_empty() {
+            self.alive.push(self.is_terminal.clone());
+        }
+        while self.alive.len() <= r {
+            let last = self.alive.last().unwrap();
+            let next = self.transitions.iter()
+                .map(|edges| edges.iter().any(|(_, next_state)| last[*next_state]))
+                .collect();
+            self.alive.push(next);
+        }
+        &self.alive[r]
+    }
+
+    /// Depth-first search that only follows transitions which can still
+    /// complete a word of the current length, so it never backtracks from a
+    /// dead end.
+    fn next_of_length(&mut self) -> Option<String> {
+        while let Some((state, next)) = self.stack.last().copied() {
+            let remaining = self.len - self.word.len();
+            if remaining == 0 {
+                let word = self.word.iter().collect();
+                self.stack.pop();
+                self.word.pop();
+                return Some(word);
+            }
+            let edge = (next..self.transitions[state].len())
+                .find(|i| self.alive[remaining - 1][self.transitions[state][*i].1]);
+            match edge {
+                Some(i) => {
+                    let (ch, next_state) = self.transitions[state][i];
+                    self.stack.last_mut().unwrap().1 = i + 1;
+                    self.stack.push((next_state, 0));
+                    self.word.push(ch);
+                }
+                None => {
+                    self.stack.pop();
+                    self.word.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Iterator for Words {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let start = self.starting_state?;
+        loop {
+            if self.exhausted || self.limit.is_some_and(|limit| self.produced >= limit) {
+                return None;
+            }
+            if !self.started {
+                let len = self.len;
+                if self.max_len.is_some_and(|max_len| len > max_len) || !self.alive(len).contains(&true) {
+                    self.exhausted = true;
+                    return None;
+                }
+                self.started = true;
+                if self.alive[len][start] {
+                    self.stack.push((start, 0));
+                }
+            }
+            if let Some(word) = self.next_of_length() {
+                self.produced += 1;
+                return Some(word);
+            }
+            self.started = false;
+            self.len += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
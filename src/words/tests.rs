@@ -0,0 +1,57 @@
+use crate::dfa::*;
+use crate::nfa::*;
+use crate::words::*;
+use crate::testing::*;
+
+#[test]
+fn shortlex_order_test() {
+    let words: Vec<String> = from_regex("(a+b)*").words().limit(7).collect();
+    assert_eq!(words, vec!["", "a", "b", "aa", "ab", "ba", "bb"]);
+
+    let words: Vec<String> = from_regex("b(a+b)+a").words().collect();
+    assert_eq!(words, vec!["a", "ba", "bb"]);
+}
+
+#[test]
+fn finite_language_test() {
+    let words: Vec<String> = from_regex("c(a+b)(a+b)").to_dfa().words().collect();
+    assert_eq!(words, vec!["caa", "cab", "cba", "cbb"]);
+
+    let mut q0: NfaState<char> = NfaState::new(0, false);
+    q0.add_transition('a', 1);
+    q0.add_transition('a', 0);
+    let q1: NfaState<char> = NfaState::new(1, false);
+    let mut empty: Nfa<char> = Nfa::new(0);
+    empty.add_state(q0);
+    empty.add_state(q1);
+    assert_eq!(empty.words().next(), None);
+}
+
+#[test]
+fn length_bounds_test() {
+    let words: Vec<String> = from_regex("a(aa)*").words().min_len(2).max_len(7).collect();
+    assert_eq!(words, vec!["aaa", "aaaaa", "aaaaaaa"]);
+
+    let words: Vec<String> = from_regex("(ab)*").words().min_len(3).limit(2).collect();
+    assert_eq!(words, vec!["abab", "ababab"]);
+
+    assert_eq!(from_regex("(a+b)*").words().max_len(10).count(), 2047);
+}
+
+#[test]
+fn dead_states_test() {
+    let mut q0 = DfaState::new(0, false);
+    q0.add_transition('a', 1);
+    q0.add_transition('b', 2);
+    let mut q1 = DfaState::new(1, false);
+    q1.add_transition('a', 1);
+    let q2 = DfaState::new(2, true);
+
+    let mut dfa = Dfa::new(0);
+    dfa.add_state(q0);
+    dfa.add_state(q1);
+    dfa.add_state(q2);
+
+    let words: Vec<String> = dfa.words().collect();
+    assert_eq!(words, vec!["b"]);
+}